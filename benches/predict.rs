@@ -0,0 +1,147 @@
+//! Prediction benchmarks
+//!
+//! Trains a small synthetic model and times the prediction paths over a few batch sizes,
+//! with the input conversion timed separately from the full prediction so that the time
+//! spent inside XGBoost can be told apart.
+//!
+//! Run with `--features polars` to include the DataFrame path:
+//!
+//! ```bash
+//! cargo bench --bench predict --features polars
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use xgboost_rust::{Booster, BoosterBuilder, DMatrix};
+
+const NUM_FEATURES: usize = 20;
+const TRAIN_ROWS: usize = 5_000;
+const BATCH_SIZES: [usize; 3] = [1, 100, 10_000];
+
+fn predict(c: &mut Criterion) {
+    let booster = train_fixture();
+
+    let mut group = c.benchmark_group("predict");
+    for &rows in &BATCH_SIZES {
+        let data = synthetic_data(rows, 42);
+        group.throughput(Throughput::Elements(rows as u64));
+
+        // Booster::predict builds a DMatrix and predicts on it. XGBoost caches predictions
+        // per DMatrix, so the predict half can't be timed on its own; compare with from_dense
+        group.bench_with_input(BenchmarkId::new("dense", rows), &data, |b, data| {
+            b.iter(|| {
+                booster
+                    .predict(black_box(data), rows, NUM_FEATURES, 0, false)
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("from_dense", rows), &data, |b, data| {
+            b.iter(|| DMatrix::from_dense(black_box(data), rows, NUM_FEATURES, f32::NAN).unwrap())
+        });
+
+        #[cfg(xgboost_inplace_predict)]
+        group.bench_with_input(BenchmarkId::new("inplace", rows), &data, |b, data| {
+            b.iter(|| {
+                booster
+                    .inplace_predict(black_box(data), rows, NUM_FEATURES, f32::NAN)
+                    .unwrap()
+            })
+        });
+
+        #[cfg(feature = "polars")]
+        polars_bench::run(&mut group, &booster, &data, rows);
+    }
+    group.finish();
+}
+
+/// Train a regression model on synthetic data
+fn train_fixture() -> Booster {
+    let data = synthetic_data(TRAIN_ROWS, 7);
+    let labels: Vec<f32> = data
+        .chunks(NUM_FEATURES)
+        .map(|row| {
+            // Labels must not be NaN, so read missing features as 0
+            let x: Vec<f32> = row[..4]
+                .iter()
+                .map(|v| if v.is_nan() { 0.0 } else { *v })
+                .collect();
+            x[0] * 2.0 + x[1] - x[2] * x[3]
+        })
+        .collect();
+
+    let mut dtrain = DMatrix::from_dense(&data, TRAIN_ROWS, NUM_FEATURES, f32::NAN).unwrap();
+    dtrain.set_labels(&labels).unwrap();
+    BoosterBuilder::new()
+        .objective("reg:squarederror")
+        .param("max_depth", "6")
+        .num_round(100)
+        .train(&dtrain)
+        .unwrap()
+}
+
+/// Generate reproducible row-major features in [0, 1), with about 5% missing
+fn synthetic_data(rows: usize, seed: u64) -> Vec<f32> {
+    let mut state = seed;
+    (0..rows * NUM_FEATURES)
+        .map(|_| {
+            // 64-bit linear congruential generator, good enough for benchmark inputs
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let value = (state >> 40) as f32 / (1u64 << 24) as f32;
+            if value < 0.05 {
+                f32::NAN
+            } else {
+                value
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "polars")]
+mod polars_bench {
+    use super::NUM_FEATURES;
+    use criterion::measurement::WallTime;
+    use criterion::{BenchmarkGroup, BenchmarkId};
+    use polars::prelude::*;
+    use std::hint::black_box;
+    use xgboost_rust::{dataframe_to_dense, Booster, BoosterPolarsExt};
+
+    /// Time DataFrame prediction, and the conversion to a dense buffer it starts with
+    pub fn run(
+        group: &mut BenchmarkGroup<'_, WallTime>,
+        booster: &Booster,
+        data: &[f32],
+        rows: usize,
+    ) {
+        let df = to_dataframe(data, rows);
+
+        group.bench_with_input(BenchmarkId::new("dataframe", rows), &df, |b, df| {
+            b.iter(|| {
+                booster
+                    .predict_dataframe(black_box(df), 0, false, false)
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("dataframe_to_dense", rows),
+            &df,
+            |b, df| b.iter(|| dataframe_to_dense(black_box(df)).unwrap()),
+        );
+    }
+
+    fn to_dataframe(data: &[f32], rows: usize) -> DataFrame {
+        let columns = (0..NUM_FEATURES)
+            .map(|col| {
+                let values: Vec<Option<f32>> = (0..rows)
+                    .map(|row| Some(data[row * NUM_FEATURES + col]).filter(|v| !v.is_nan()))
+                    .collect();
+                Column::new(format!("f{}", col).into(), values)
+            })
+            .collect();
+        DataFrame::new(columns).expect("columns have equal length")
+    }
+}
+
+criterion_group!(benches, predict);
+criterion_main!(benches);
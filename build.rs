@@ -6,11 +6,59 @@ use std::env;
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::thread;
 use std::time::Duration;
 
+// Cargo features that select the XGBoost version, newest first, each pinned to a release
+// with known header checksums. XGBOOST_VERSION takes precedence over them
+const VERSION_FEATURES: [(&str, &str); 5] = [
+    ("xgboost-3_1", "3.1.1"),
+    ("xgboost-3_0", "3.0.5"),
+    ("xgboost-2_1", "2.1.4"),
+    ("xgboost-1_7", "1.7.6"),
+    ("xgboost-1_4", "1.4.2"),
+];
+
 fn get_xgboost_version() -> String {
-    env::var("XGBOOST_VERSION").unwrap_or_else(|_| "3.1.1".to_string())
+    if let Ok(version) = env::var("XGBOOST_VERSION") {
+        return version;
+    }
+    enabled_version_features()
+        .first()
+        .map_or("3.1.1", |(_, version)| version)
+        .to_string()
+}
+
+fn enabled_version_features() -> Vec<(&'static str, &'static str)> {
+    VERSION_FEATURES
+        .into_iter()
+        .filter(|(feature, _)| {
+            let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+            env::var(var).is_ok()
+        })
+        .collect()
+}
+
+// Warn when the version features do not decide the version on their own
+fn check_version_features() {
+    let enabled = enabled_version_features();
+    let Some((newest, _)) = enabled.first() else {
+        return;
+    };
+    if let Ok(version) = env::var("XGBOOST_VERSION") {
+        println!(
+            "cargo:warning=XGBOOST_VERSION={} overrides the {} feature",
+            version, newest
+        );
+    } else if enabled.len() > 1 {
+        let names: Vec<&str> = enabled.iter().map(|(feature, _)| *feature).collect();
+        println!(
+            "cargo:warning=Several XGBoost version features are enabled ({}), using {}",
+            names.join(", "),
+            newest
+        );
+    }
 }
 
 // Known SHA256 checksums for header files by version
@@ -55,6 +103,15 @@ fn get_header_checksums() -> HashMap<&'static str, (&'static str, &'static str)>
     checksums
 }
 
+// Known SHA256 checksums of the wheels downloaded by default, by wheel filename
+fn get_wheel_checksums() -> HashMap<&'static str, &'static str> {
+    // Format: wheel filename => SHA256, copied from the file's "digests" on PyPI
+    // (https://pypi.org/pypi/<package>/<version>/json).
+    // No wheels are listed yet, so default downloads are only verified when
+    // XGBOOST_WHEEL_SHA256 is set; a wheel without an entry is never rejected.
+    HashMap::new()
+}
+
 fn compute_sha256(data: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(data);
@@ -103,6 +160,91 @@ fn emit_version_cfg_flags(version: &str) {
             version
         );
     }
+
+    // XGBoost 1.4.0+ predicts from a JSON config that reports the output shape
+    // (XGBoosterPredictFromDMatrix)
+    if major > 1 || (major == 1 && minor >= 4) {
+        println!("cargo:rustc-cfg=xgboost_predict_from_dmatrix");
+    }
+
+    // XGBoost 1.5.0+ exposes its build configuration (XGBuildInfo)
+    if major > 1 || (major == 1 && minor >= 5) {
+        println!("cargo:rustc-cfg=xgboost_build_info");
+    }
+
+    // XGBoost 1.6.0+ has inplace prediction from an array interface (XGBoosterPredictFromDense)
+    if major > 1 || (major == 1 && minor >= 6) {
+        println!("cargo:rustc-cfg=xgboost_inplace_predict");
+    }
+
+    // XGBoost 1.6.0+ can save a model to a memory buffer (XGBoosterSaveModelToBuffer)
+    if major > 1 || (major == 1 && minor >= 6) {
+        println!("cargo:rustc-cfg=xgboost_save_to_buffer");
+    }
+
+    // XGBoost 1.6.0+ can build an external memory DMatrix from a data iterator
+    // (XGDMatrixCreateFromCallback fed through XGProxyDMatrixSetDataDense/CSR)
+    if major > 1 || (major == 1 && minor >= 6) {
+        println!("cargo:rustc-cfg=xgboost_external_memory");
+    }
+
+    // XGBoost 1.7.0+ can read a DMatrix back as CSR (XGDMatrixGetDataAsCSR)
+    if major > 1 || (major == 1 && minor >= 7) {
+        println!("cargo:rustc-cfg=xgboost_dmatrix_csr");
+    }
+
+    // XGBoost 1.7.0+ counts the stored entries of a DMatrix (XGDMatrixNumNonMissing)
+    if major > 1 || (major == 1 && minor >= 7) {
+        println!("cargo:rustc-cfg=xgboost_num_nonmissing");
+    }
+
+    // XGBoost 2.0.0+ can release a booster's training caches (XGBoosterReset)
+    if major >= 2 {
+        println!("cargo:rustc-cfg=xgboost_reset");
+    }
+
+    // XGBoost 2.0.0+ selects CPU or GPU through the unified `device` parameter
+    if major >= 2 {
+        println!("cargo:rustc-cfg=xgboost_device");
+    }
+}
+
+// Run a tool that patches the library (install_name_tool or patchelf), warning instead of
+// failing the build since the rpath usually still finds the library. Returns false if the
+// tool is not installed, with `missing_hint` explaining the consequence
+fn run_patch_tool(mut command: Command, missing_hint: &str) -> bool {
+    let tool = command.get_program().to_string_lossy().into_owned();
+    // The library being patched is the last argument
+    let lib_path = command
+        .get_args()
+        .last()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match command.output() {
+        Ok(output) if output.status.success() => true,
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            println!(
+                "cargo:warning={} failed on {} ({}): {}",
+                tool,
+                lib_path,
+                output.status,
+                stderr.split_whitespace().collect::<Vec<_>>().join(" ")
+            );
+            true
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            println!("cargo:warning={} not found: {}", tool, missing_hint);
+            false
+        }
+        Err(e) => {
+            println!(
+                "cargo:warning=Could not run {} on {}: {}",
+                tool, lib_path, e
+            );
+            true
+        }
+    }
 }
 
 fn get_platform_info() -> (String, String) {
@@ -177,6 +319,34 @@ fn download_xgboost_headers(out_dir: &Path) -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
+// Read a positive integer setting from the environment, using `default` when it is unset
+fn env_positive_u64(name: &str, default: u64) -> Result<u64, Box<dyn std::error::Error>> {
+    match env::var(name) {
+        Ok(value) => match value.trim().parse::<u64>() {
+            Ok(parsed) if parsed > 0 => Ok(parsed),
+            _ => Err(format!("Invalid {} '{}', expected a positive integer", name, value).into()),
+        },
+        Err(_) => Ok(default),
+    }
+}
+
+// Number of attempts for each download (XGBOOST_DOWNLOAD_RETRIES, default 3)
+fn download_attempts() -> Result<u32, Box<dyn std::error::Error>> {
+    Ok(env_positive_u64("XGBOOST_DOWNLOAD_RETRIES", 3)?.min(u32::MAX as u64) as u32)
+}
+
+// HTTP agent for all downloads, honouring the standard HTTP_PROXY/HTTPS_PROXY/NO_PROXY
+// environment variables. XGBOOST_DOWNLOAD_TIMEOUT_SECS (default 30) bounds connecting and
+// each read, so a stalled download fails without limiting the total time of a large one.
+fn http_agent() -> Result<ureq::Agent, Box<dyn std::error::Error>> {
+    let timeout = Duration::from_secs(env_positive_u64("XGBOOST_DOWNLOAD_TIMEOUT_SECS", 30)?);
+    Ok(ureq::AgentBuilder::new()
+        .try_proxy_from_env(true)
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .build())
+}
+
 fn download_and_verify_file(
     url: &str,
     dest_path: &Path,
@@ -186,14 +356,8 @@ fn download_and_verify_file(
     println!("cargo:warning=Downloading {} from: {}", filename, url);
 
     // Download into memory buffer
-    let response = ureq::get(url).call()?;
-    let status = response.status();
-    if !(200..300).contains(&status) {
-        return Err(format!("Failed to download {}: HTTP {}", filename, status).into());
-    }
-
-    let mut buffer = Vec::new();
-    response.into_reader().read_to_end(&mut buffer)?;
+    let buffer = download_with_retry(url, download_attempts()?)
+        .map_err(|e| format!("Failed to download {}: {}", filename, e))?;
 
     // Verify SHA256 checksum
     verify_checksum(&buffer, expected_sha256, filename)?;
@@ -206,6 +370,7 @@ fn download_and_verify_file(
 }
 
 fn download_with_retry(url: &str, max_retries: u32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let agent = http_agent()?;
     let mut last_error = None;
 
     for attempt in 0..max_retries {
@@ -219,14 +384,8 @@ fn download_with_retry(url: &str, max_retries: u32) -> Result<Vec<u8>, Box<dyn s
             thread::sleep(backoff);
         }
 
-        match ureq::get(url).call() {
+        match agent.get(url).call() {
             Ok(response) => {
-                let status = response.status();
-                if !(200..300).contains(&status) {
-                    last_error = Some(format!("HTTP {}", status));
-                    continue;
-                }
-
                 let mut buffer = Vec::new();
                 if let Err(e) = response.into_reader().read_to_end(&mut buffer) {
                     last_error = Some(e.to_string());
@@ -235,6 +394,10 @@ fn download_with_retry(url: &str, max_retries: u32) -> Result<Vec<u8>, Box<dyn s
 
                 return Ok(buffer);
             }
+            // A client error such as 404 (wrong version or flavor) will not go away on retry
+            Err(ureq::Error::Status(code, _)) if code < 500 => {
+                return Err(format!("Failed to download {}: HTTP {}", url, code).into());
+            }
             Err(e) => {
                 last_error = Some(e.to_string());
             }
@@ -254,19 +417,60 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
     let version = get_xgboost_version();
     let (major, minor, _patch) = parse_version(&version);
 
+    // XGBOOST_WHEEL_FLAVOR selects the CPU-only or CUDA-enabled wheel on Linux x86_64
+    let flavor = env::var("XGBOOST_WHEEL_FLAVOR")
+        .ok()
+        .map(|flavor| flavor.trim().to_lowercase())
+        .filter(|flavor| !flavor.is_empty());
+    match flavor.as_deref() {
+        None | Some("cpu") | Some("gpu") => {}
+        Some(other) => {
+            return Err(format!(
+                "Invalid XGBOOST_WHEEL_FLAVOR '{}', expected 'cpu' or 'gpu'",
+                other
+            )
+            .into())
+        }
+    }
+    if flavor.as_deref() == Some("gpu") && (os.as_str(), arch.as_str()) != ("linux", "x86_64") {
+        println!(
+            "cargo:warning=No GPU wheel is published for {}-{}, using the CPU wheel",
+            os, arch
+        );
+    }
+
+    // Since 2.1.0 the manylinux_2_28 wheel of the `xgboost` package bundles CUDA,
+    // and the CPU-only build is published separately as the `xgboost-cpu` package
+    let split_cpu_package = major > 2 || (major == 2 && minor >= 1);
+    let package = match (os.as_str(), arch.as_str(), flavor.as_deref()) {
+        ("linux", "x86_64", Some("cpu")) if split_cpu_package => "xgboost_cpu",
+        ("linux", "x86_64", Some("cpu")) => {
+            // Before 2.1.0 there is a single wheel, which includes CUDA
+            println!(
+                "cargo:warning=XGBoost {} has no CPU-only wheel, using the default wheel",
+                version
+            );
+            "xgboost"
+        }
+        _ => "xgboost",
+    };
+
     // Determine wheel filename based on platform and version
     // Different XGBoost versions use different manylinux tags
     let wheel_filename = match (os.as_str(), arch.as_str()) {
         ("linux", "x86_64") => {
             // Choose manylinux tag based on version
-            let manylinux_tag = if major >= 3 {
+            let manylinux_tag = if major >= 3 || (split_cpu_package && flavor.is_some()) {
                 "manylinux_2_28"
             } else if major == 1 && minor == 4 {
                 "manylinux2010"
             } else {
                 "manylinux2014"
             };
-            format!("xgboost-{}-py3-none-{}_x86_64.whl", version, manylinux_tag)
+            format!(
+                "{}-{}-py3-none-{}_x86_64.whl",
+                package, version, manylinux_tag
+            )
         }
         ("linux", "aarch64") => {
             let manylinux_tag = if major >= 3 {
@@ -316,8 +520,40 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
     let wheel_path = wheel_dir.join(&wheel_filename);
     let lib_dest_path = lib_dir.join(lib_filename);
 
-    // Check if library already exists and is valid
-    if lib_dest_path.exists() {
+    // Download from XGBOOST_WHEEL_URL verbatim if set
+    let download_url = env::var("XGBOOST_WHEEL_URL").unwrap_or_else(|_| {
+        format!(
+            "https://files.pythonhosted.org/packages/py3/x/{}/{}",
+            package, wheel_filename
+        )
+    });
+
+    // Expected checksum of the wheel: the one the user pinned, or the known checksum of a
+    // wheel downloaded from its default location
+    let expected_wheel_sha256 = env::var("XGBOOST_WHEEL_SHA256")
+        .ok()
+        .map(|sha256| sha256.trim().to_lowercase())
+        .or_else(|| {
+            if env::var("XGBOOST_WHEEL_URL").is_ok() {
+                return None;
+            }
+            get_wheel_checksums()
+                .get(wheel_filename.as_str())
+                .map(|sha256| sha256.to_string())
+        });
+
+    // Reuse the library extracted by a previous build only if it came from the same wheel,
+    // verified against the same checksum: OUT_DIR stays the same when XGBOOST_VERSION,
+    // XGBOOST_WHEEL_FLAVOR or XGBOOST_WHEEL_SHA256 change
+    let wheel_source = format!(
+        "{} from {} with SHA256 {}",
+        wheel_filename,
+        download_url,
+        expected_wheel_sha256.as_deref().unwrap_or("unverified")
+    );
+    let source_marker_path = lib_dir.join(format!("{}.source", lib_filename));
+    let cached_source = fs::read_to_string(&source_marker_path).ok();
+    if lib_dest_path.exists() && cached_source.as_deref() == Some(wheel_source.as_str()) {
         println!(
             "cargo:warning=Using cached XGBoost library at: {}",
             lib_dest_path.display()
@@ -325,25 +561,48 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
         return Ok(());
     }
 
-    // Check if wheel is cached
-    let wheel_buffer = if wheel_path.exists() {
-        println!(
-            "cargo:warning=Using cached wheel at: {}",
-            wheel_path.display()
-        );
-        fs::read(&wheel_path)?
+    // Check if wheel is cached, discarding it if it doesn't match the expected checksum
+    let cached_wheel = if wheel_path.exists() {
+        let buffer = fs::read(&wheel_path)?;
+        match &expected_wheel_sha256 {
+            Some(expected) if compute_sha256(&buffer) != *expected => {
+                println!(
+                    "cargo:warning=Cached wheel at {} does not match the expected SHA256, re-downloading",
+                    wheel_path.display()
+                );
+                fs::remove_file(&wheel_path)?;
+                None
+            }
+            _ => {
+                println!(
+                    "cargo:warning=Using cached wheel at: {}",
+                    wheel_path.display()
+                );
+                Some(buffer)
+            }
+        }
     } else {
-        // Download wheel with retry
-        let download_url = format!(
-            "https://files.pythonhosted.org/packages/py3/x/xgboost/{}",
-            wheel_filename
-        );
+        None
+    };
 
+    let wheel_buffer = if let Some(buffer) = cached_wheel {
+        buffer
+    } else {
+        // Download wheel with retry
         println!(
             "cargo:warning=Downloading XGBoost wheel from: {}",
             download_url
         );
-        let buffer = download_with_retry(&download_url, 3)?;
+        let buffer = download_with_retry(&download_url, download_attempts()?)?;
+
+        // Verify SHA256 checksum before caching the wheel
+        match &expected_wheel_sha256 {
+            Some(expected) => verify_checksum(&buffer, expected, &wheel_filename)?,
+            None => println!(
+                "cargo:warning=Wheel SHA256 is {} (set XGBOOST_WHEEL_SHA256 to pin it)",
+                compute_sha256(&buffer)
+            ),
+        }
 
         // Write atomically (temp file + rename)
         let temp_path = wheel_path.with_extension("tmp");
@@ -392,6 +651,7 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
                 dest.sync_all()?;
             }
             fs::rename(&temp_dest_path, &lib_dest_path)?;
+            fs::write(&source_marker_path, &wheel_source)?;
 
             found = true;
             break;
@@ -421,35 +681,224 @@ fn download_and_extract_wheel(out_dir: &Path) -> Result<(), Box<dyn std::error::
     Ok(())
 }
 
+// Whether a flag is set in the environment, to any value other than empty or "0"
+fn env_flag(name: &str) -> bool {
+    env::var(name).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+// Find an installed XGBoost with pkg-config, which also emits the flags to link it. If it
+// cannot be found, fall back to downloading the wheel unless XGBOOST_PKG_CONFIG_STRICT is set.
+fn probe_system_library() -> Option<pkg_config::Library> {
+    match pkg_config::Config::new().probe("xgboost") {
+        Ok(library) => Some(library),
+        Err(e) if env_flag("XGBOOST_PKG_CONFIG_STRICT") => {
+            panic!("pkg-config could not find XGBoost: {}", e)
+        }
+        Err(e) => {
+            // Cargo warnings are a single line, and pkg-config errors span several
+            let message = e
+                .to_string()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            println!(
+                "cargo:warning=pkg-config could not find XGBoost, downloading it instead: {}",
+                message
+            );
+            None
+        }
+    }
+}
+
+// Find the directory cargo writes the final artifacts to, e.g. target/debug or
+// target/<triple>/release, from OUT_DIR, which is <artifact dir>/build/<package>-<hash>/out.
+// Unlike looking for a directory named `target`, this works with any CARGO_TARGET_DIR and
+// with custom profiles
+fn artifact_dir(out_dir: &Path) -> Result<PathBuf, String> {
+    out_dir
+        .parent()
+        .and_then(Path::parent)
+        .filter(|build_dir| build_dir.ends_with("build"))
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            format!(
+                "Could not find the target directory from OUT_DIR {}, which should end in \
+                 <profile>/build/<package>/out. Set XGBOOST_NO_LIB_COPY=1 to skip copying \
+                 the library next to the build output",
+                out_dir.display()
+            )
+        })
+}
+
+// Copy the shared library into an output directory, warning instead of failing since a
+// previous copy may still exist and be in use
+fn copy_library(source: &Path, dest: &Path) {
+    let result = dest
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::copy(source, dest));
+    if let Err(e) = result {
+        println!(
+            "cargo:warning=Could not copy {} to {}: {} (non-fatal, library may already exist)",
+            source.display(),
+            dest.display(),
+            e
+        );
+    }
+}
+
+fn emit_static_link_flags(os: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let lib_dir = PathBuf::from(env::var("XGBOOST_LIB_DIR").map_err(|_| {
+        "The `static` feature requires XGBOOST_LIB_DIR to be set to the directory \
+         containing the XGBoost static library"
+    })?);
+
+    let static_lib = |name: &str| {
+        if os == "windows" {
+            lib_dir.join(format!("{}.lib", name))
+        } else {
+            lib_dir.join(format!("lib{}.a", name))
+        }
+    };
+
+    let xgboost_lib = static_lib("xgboost");
+    if !xgboost_lib.exists() {
+        return Err(format!(
+            "Static XGBoost library not found at {}. Build XGBoost with \
+             -DBUILD_STATIC_LIB=ON and point XGBOOST_LIB_DIR at the output directory",
+            xgboost_lib.display()
+        )
+        .into());
+    }
+
+    println!("cargo:rerun-if-changed={}", xgboost_lib.display());
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    println!("cargo:rustc-link-lib=static=xgboost");
+
+    // Older XGBoost builds ship dmlc and rabit as separate archives
+    for dep in ["dmlc", "rabit"] {
+        if static_lib(dep).exists() {
+            println!("cargo:rustc-link-lib=static={}", dep);
+        }
+    }
+
+    // The C++ runtime and OpenMP are always linked dynamically
+    match os {
+        "linux" => {
+            println!("cargo:rustc-link-lib=dylib=stdc++");
+            println!("cargo:rustc-link-lib=dylib=gomp");
+        }
+        "darwin" => {
+            println!("cargo:rustc-link-lib=dylib=c++");
+            println!("cargo:rustc-link-lib=dylib=omp");
+        }
+        _ => {} // MSVC links its C++ runtime and OpenMP automatically
+    }
+
+    println!(
+        "cargo:warning=Linking XGBoost statically from: {}",
+        lib_dir.display()
+    );
+
+    Ok(())
+}
+
 fn main() {
     // Tell cargo about custom cfg flags we emit
     println!("cargo:rustc-check-cfg=cfg(xgboost_thread_safe)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_predict_from_dmatrix)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_build_info)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_inplace_predict)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_save_to_buffer)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_external_memory)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_dmatrix_csr)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_num_nonmissing)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_reset)");
+    println!("cargo:rustc-check-cfg=cfg(xgboost_device)");
+
+    // Rerun when an input of the build changes. Without any rerun-if directive cargo only
+    // reruns when a file in the package changes, so a new XGBOOST_VERSION would keep
+    // linking the old library. The proxy, retry and timeout variables are left out since
+    // they only affect how files are downloaded
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=wrapper.h");
+    for var in [
+        "TARGET",
+        "XGBOOST_VERSION",
+        "XGBOOST_WHEEL_URL",
+        "XGBOOST_WHEEL_SHA256",
+        "XGBOOST_WHEEL_FLAVOR",
+        "XGBOOST_LIB_DIR",
+        "XGBOOST_USE_PKG_CONFIG",
+        "XGBOOST_PKG_CONFIG_STRICT",
+        "XGBOOST_NO_LIB_COPY",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    check_version_features();
 
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
-    let xgb_include_root = out_dir.join("include");
+
+    // With the `static` feature, link against a local static build instead of the wheel
+    let link_static = env::var("CARGO_FEATURE_STATIC").is_ok();
+
+    // With XGBOOST_USE_PKG_CONFIG, link an installed library instead of downloading one
+    let system_library = if !link_static && env_flag("XGBOOST_USE_PKG_CONFIG") {
+        probe_system_library()
+    } else {
+        None
+    };
 
     // Get version and emit cfg flags for thread safety
-    let version = get_xgboost_version();
+    let version = match &system_library {
+        Some(library) => {
+            if env::var("XGBOOST_VERSION").is_ok_and(|requested| requested != library.version) {
+                println!(
+                    "cargo:warning=Using the installed XGBoost {} instead of XGBOOST_VERSION",
+                    library.version
+                );
+            }
+            library.version.clone()
+        }
+        None => get_xgboost_version(),
+    };
     emit_version_cfg_flags(&version);
-
-    // Download the headers
-    if let Err(e) = download_xgboost_headers(&out_dir) {
-        eprintln!("Failed to download XGBoost headers: {}", e);
-        panic!("Cannot proceed without headers");
-    }
+    // Let the crate check at run time that the linked library matches the headers
+    println!("cargo:rustc-env=XGBOOST_RUST_HEADER_VERSION={}", version);
+
+    let include_paths = match &system_library {
+        Some(library) => library.include_paths.clone(),
+        None => {
+            // Download the headers
+            if let Err(e) = download_xgboost_headers(&out_dir) {
+                eprintln!("Failed to download XGBoost headers: {}", e);
+                panic!("Cannot proceed without headers");
+            }
+            vec![out_dir.join("include")]
+        }
+    };
 
     // Download and extract the wheel
-    if let Err(e) = download_and_extract_wheel(&out_dir) {
-        eprintln!("Failed to download and extract wheel: {}", e);
-        panic!("Cannot proceed without compiled library");
+    if !link_static && system_library.is_none() {
+        if let Err(e) = download_and_extract_wheel(&out_dir) {
+            eprintln!("Failed to download and extract wheel: {}", e);
+            panic!("Cannot proceed without compiled library");
+        }
     }
 
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
-        .clang_arg(format!("-I{}", xgb_include_root.display()))
+        .clang_args(
+            include_paths
+                .iter()
+                .map(|path| format!("-I{}", path.display())),
+        )
         // Generate bindings for XGB and XGD functions (Booster and DMatrix)
         .allowlist_function("XGB.*")
         .allowlist_function("XGD.*")
+        .allowlist_function("XGProxy.*")
         // Allowlist the main types we need
         .allowlist_type("BoosterHandle")
         .allowlist_type("DMatrixHandle")
@@ -467,6 +916,25 @@ fn main() {
     // Get platform info
     let (os, _arch) = get_platform_info();
 
+    // pkg-config has already emitted the link flags; only non-standard library
+    // directories (e.g. in the Nix store) need an rpath
+    if let Some(library) = &system_library {
+        if os == "linux" || os == "darwin" {
+            for link_path in &library.link_paths {
+                println!("cargo:rustc-link-arg=-Wl,-rpath,{}", link_path.display());
+            }
+        }
+        return;
+    }
+
+    if link_static {
+        if let Err(e) = emit_static_link_flags(&os) {
+            eprintln!("Failed to link XGBoost statically: {}", e);
+            panic!("Cannot proceed without static library");
+        }
+        return;
+    }
+
     // Determine the library filename based on the OS
     let lib_filename = match os.as_str() {
         "windows" => "xgboost.dll",
@@ -477,49 +945,67 @@ fn main() {
     // Copy the library from OUT_DIR/libs to the final target directory
     let lib_source_path = out_dir.join("libs").join(lib_filename);
 
-    // Find the final output directory (e.g., target/release)
-    let target_dir = out_dir
-        .ancestors()
-        .find(|p| p.ends_with("target"))
-        .unwrap()
-        .join(env::var("PROFILE").unwrap());
-
-    let lib_dest_path = target_dir.join(lib_filename);
-    if let Err(e) = fs::copy(&lib_source_path, &lib_dest_path) {
-        println!(
-            "cargo:warning=Could not copy {} to {}: {} (non-fatal, library may already exist)",
-            lib_source_path.display(),
-            lib_dest_path.display(),
-            e
-        );
-    }
+    // Copy into the final output directory (e.g., target/release) unless disabled, e.g. for
+    // workspaces where several build scripts would race to write the same file. On Linux and
+    // macOS the rpath to OUT_DIR/libs below is enough to find the library either way.
+    let lib_dest_path = if env_flag("XGBOOST_NO_LIB_COPY") {
+        if os == "windows" {
+            println!(
+                "cargo:warning=XGBOOST_NO_LIB_COPY is set, so add {} to PATH to run binaries",
+                lib_source_path.parent().unwrap().display()
+            );
+        }
+        None
+    } else {
+        let target_dir = artifact_dir(&out_dir).unwrap_or_else(|e| panic!("{}", e));
+
+        let lib_dest_path = target_dir.join(lib_filename);
+        copy_library(&lib_source_path, &lib_dest_path);
+
+        // Windows has no rpath and looks for DLLs next to the executable, so also copy the
+        // library next to test binaries and examples
+        if os == "windows" {
+            for subdir in ["deps", "examples"] {
+                copy_library(
+                    &lib_source_path,
+                    &target_dir.join(subdir).join(lib_filename),
+                );
+            }
+        }
+        Some(lib_dest_path)
+    };
 
     // On macOS/Linux, change the install name/soname to use @loader_path/$ORIGIN
     if os == "darwin" {
-        use std::process::Command;
-        let _ = Command::new("install_name_tool")
-            .arg("-id")
-            .arg(format!("@loader_path/{}", lib_filename))
-            .arg(&lib_source_path)
-            .status();
-        let _ = Command::new("install_name_tool")
-            .arg("-id")
-            .arg(format!("@loader_path/{}", lib_filename))
-            .arg(&lib_dest_path)
-            .status();
+        for lib_path in std::iter::once(&lib_source_path).chain(&lib_dest_path) {
+            let mut command = Command::new("install_name_tool");
+            command
+                .arg("-id")
+                .arg(format!("@loader_path/{}", lib_filename))
+                .arg(lib_path);
+            let hint = format!(
+                "the install name of {} was not set, so binaries may fail to load it at run time. \
+                 Install the Xcode Command Line Tools (xcode-select --install) and rebuild",
+                lib_filename
+            );
+            if !run_patch_tool(command, &hint) {
+                break;
+            }
+        }
     } else if os == "linux" {
-        use std::process::Command;
         // Use patchelf to set soname (if available)
-        let _ = Command::new("patchelf")
-            .arg("--set-soname")
-            .arg(lib_filename)
-            .arg(&lib_source_path)
-            .output();
-        let _ = Command::new("patchelf")
-            .arg("--set-soname")
-            .arg(lib_filename)
-            .arg(&lib_dest_path)
-            .output();
+        for lib_path in std::iter::once(&lib_source_path).chain(&lib_dest_path) {
+            let mut command = Command::new("patchelf");
+            command.arg("--set-soname").arg(lib_filename).arg(lib_path);
+            let hint = format!(
+                "the soname of {} was not set. If binaries fail to find the library at run \
+                 time, install patchelf (e.g. apt-get install patchelf) and rebuild",
+                lib_filename
+            );
+            if !run_patch_tool(command, &hint) {
+                break;
+            }
+        }
     }
 
     // Set the library search path for the build-time linker
@@ -529,6 +1015,11 @@ fn main() {
         lib_search_path.display()
     );
 
+    // The directory holding the debug and release directories, if it can be found
+    let target_root = artifact_dir(&out_dir)
+        .ok()
+        .and_then(|dir| dir.parent().map(Path::to_path_buf));
+
     // Set the rpath for the run-time linker based on the OS
     match os.as_str() {
         "darwin" => {
@@ -542,7 +1033,7 @@ fn main() {
                 lib_search_path.display()
             );
             // Add the target directory to rpath as well
-            if let Some(target_root) = out_dir.ancestors().find(|p| p.ends_with("target")) {
+            if let Some(target_root) = &target_root {
                 println!(
                     "cargo:rustc-link-arg=-Wl,-rpath,{}/debug",
                     target_root.display()
@@ -562,7 +1053,7 @@ fn main() {
                 lib_search_path.display()
             );
             // Add the target directory to rpath as well
-            if let Some(target_root) = out_dir.ancestors().find(|p| p.ends_with("target")) {
+            if let Some(target_root) = &target_root {
                 println!(
                     "cargo:rustc-link-arg=-Wl,-rpath,{}/debug",
                     target_root.display()
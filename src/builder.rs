@@ -0,0 +1,88 @@
+use crate::dmatrix::DMatrix;
+use crate::error::XGBoostResult;
+use crate::model::Booster;
+
+/// Builder for creating a trainable [`Booster`]
+///
+/// Parameters are applied in the order they were added, after the booster is created.
+///
+/// # Example
+/// ```no_run
+/// use xgboost_rust::{BoosterBuilder, DMatrix};
+///
+/// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+/// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+///
+/// let booster = BoosterBuilder::new()
+///     .objective("reg:squarederror")
+///     .param("max_depth", "6")
+///     .num_round(100)
+///     .train(&dtrain)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoosterBuilder {
+    params: Vec<(String, String)>,
+    num_round: usize,
+}
+
+impl Default for BoosterBuilder {
+    fn default() -> Self {
+        BoosterBuilder {
+            params: Vec::new(),
+            num_round: 10,
+        }
+    }
+}
+
+impl BoosterBuilder {
+    /// Create a builder with no parameters and 10 boosting rounds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a booster parameter
+    ///
+    /// # Arguments
+    /// * `name` - Parameter name, e.g. `max_depth`
+    /// * `value` - Parameter value, e.g. `6`
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.params.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Set the learning objective, e.g. `reg:squarederror` or `binary:logistic`
+    pub fn objective(self, objective: &str) -> Self {
+        self.param("objective", objective)
+    }
+
+    /// Set the number of boosting rounds run by [`BoosterBuilder::train`]
+    pub fn num_round(mut self, num_round: usize) -> Self {
+        self.num_round = num_round;
+        self
+    }
+
+    /// Create the booster and apply the parameters, without training it
+    ///
+    /// # Arguments
+    /// * `cache` - Matrices to keep in the booster's prediction cache, usually the training data
+    pub fn build(&self, cache: &[&DMatrix]) -> XGBoostResult<Booster> {
+        let mut booster = Booster::new(cache)?;
+        for (name, value) in &self.params {
+            booster.set_param(name, value)?;
+        }
+        Ok(booster)
+    }
+
+    /// Create the booster and train it on `dtrain` for the configured number of rounds
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data, with labels set
+    pub fn train(&self, dtrain: &DMatrix) -> XGBoostResult<Booster> {
+        let mut booster = self.build(&[dtrain])?;
+        for iteration in 0..self.num_round {
+            booster.update_one_iter(iteration as i32, dtrain)?;
+        }
+        Ok(booster)
+    }
+}
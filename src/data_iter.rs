@@ -0,0 +1,322 @@
+use crate::dmatrix::{validate_compressed, validate_dense_shape, Compressed, DMatrix};
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::json_f32;
+use crate::sys;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+
+/// A source of data batches for an external memory [`DMatrix`]
+///
+/// XGBoost pulls batches with `next` until it returns `None`, and calls `reset` before
+/// every pass over the data. Every pass must produce the same batches in the same order.
+/// See [`DMatrix::from_iter`].
+pub trait DataIter {
+    /// Produce the next batch, or `None` once every batch has been produced
+    ///
+    /// Returning an error stops the pass; the error is returned by
+    /// [`DMatrix::from_iter`] if it happens while the matrix is being built.
+    fn next(&mut self) -> Option<XGBoostResult<Batch>>;
+
+    /// Go back to the first batch
+    fn reset(&mut self);
+}
+
+/// One batch of rows produced by a [`DataIter`], with optional labels and weights
+///
+/// # Example
+/// ```
+/// use xgboost_rust::Batch;
+///
+/// // 2 rows, 3 features: [[1, _, 2], [_, 3, _]]
+/// let batch = Batch::csr(vec![0, 2, 3], vec![0, 2, 1], vec![1.0, 2.0, 3.0], 3)
+///     .unwrap()
+///     .with_labels(vec![0.0, 1.0])
+///     .unwrap();
+/// assert_eq!(batch.num_rows(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Batch {
+    data: BatchData,
+    labels: Option<Vec<f32>>,
+    weights: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BatchData {
+    Dense {
+        data: Vec<f32>,
+        num_rows: usize,
+        num_features: usize,
+    },
+    Csr {
+        indptr: Vec<u64>,
+        indices: Vec<u32>,
+        data: Vec<f32>,
+        num_features: usize,
+    },
+}
+
+impl Batch {
+    /// Create a batch from dense data
+    ///
+    /// # Arguments
+    /// * `data` - Features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the batch
+    /// * `num_features` - Number of features per row
+    pub fn dense(data: Vec<f32>, num_rows: usize, num_features: usize) -> XGBoostResult<Self> {
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+        Ok(Batch {
+            data: BatchData::Dense {
+                data,
+                num_rows,
+                num_features,
+            },
+            labels: None,
+            weights: None,
+        })
+    }
+
+    /// Create a batch from sparse data in CSR (compressed sparse row) format
+    ///
+    /// The entries of row `i` are `indptr[i]..indptr[i + 1]` into `indices` and `data`.
+    ///
+    /// # Arguments
+    /// * `indptr` - Offsets of each row's entries, with one more element than rows
+    /// * `indices` - Column index of each entry
+    /// * `data` - Value of each entry
+    /// * `num_features` - Number of features of the matrix
+    pub fn csr(
+        indptr: Vec<u64>,
+        indices: Vec<u32>,
+        data: Vec<f32>,
+        num_features: usize,
+    ) -> XGBoostResult<Self> {
+        validate_compressed(Compressed::Csr, &indptr, &indices, data.len(), num_features)?;
+
+        Ok(Batch {
+            data: BatchData::Csr {
+                indptr,
+                indices,
+                data,
+                num_features,
+            },
+            labels: None,
+            weights: None,
+        })
+    }
+
+    /// Attach one label per row
+    pub fn with_labels(mut self, labels: Vec<f32>) -> XGBoostResult<Self> {
+        self.check_row_count(labels.len())?;
+        self.labels = Some(labels);
+        Ok(self)
+    }
+
+    /// Attach one weight per row
+    pub fn with_weights(mut self, weights: Vec<f32>) -> XGBoostResult<Self> {
+        self.check_row_count(weights.len())?;
+        self.weights = Some(weights);
+        Ok(self)
+    }
+
+    /// Get the number of rows in the batch
+    pub fn num_rows(&self) -> usize {
+        match &self.data {
+            BatchData::Dense { num_rows, .. } => *num_rows,
+            BatchData::Csr { indptr, .. } => indptr.len() - 1,
+        }
+    }
+
+    fn check_row_count(&self, len: usize) -> XGBoostResult<()> {
+        if len != self.num_rows() {
+            return Err(XGBoostError::DimensionMismatch {
+                expected: self.num_rows(),
+                got: len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// State behind the iterator handle XGBoost passes to the callbacks
+///
+/// Owned by the [`DMatrix`] built from it, since XGBoost keeps calling the iterator
+/// while training on an external memory matrix.
+pub(crate) struct IterSource {
+    iter: Box<dyn DataIter>,
+    proxy: DMatrix,
+    // The batch the proxy currently points into, kept alive until the next call
+    current: Option<Batch>,
+    error: Option<XGBoostError>,
+}
+
+impl IterSource {
+    /// Fetch the next batch from the iterator and point the proxy at it
+    fn advance(&mut self) -> XGBoostResult<bool> {
+        let batch = match self.iter.next().transpose()? {
+            Some(batch) => batch,
+            None => {
+                self.current = None;
+                return Ok(false);
+            }
+        };
+
+        match &batch.data {
+            BatchData::Dense {
+                data,
+                num_rows,
+                num_features,
+            } => {
+                let interface = CString::new(crate::model::dense_array_interface(
+                    data,
+                    *num_rows,
+                    *num_features,
+                ))?;
+                XGBoostError::check_return_value(unsafe {
+                    sys::XGProxyDMatrixSetDataDense(self.proxy.handle(), interface.as_ptr())
+                })?;
+            }
+            BatchData::Csr {
+                indptr,
+                indices,
+                data,
+                num_features,
+            } => {
+                let indptr = CString::new(vector_interface(indptr, "u8"))?;
+                let indices = CString::new(vector_interface(indices, "u4"))?;
+                let data = CString::new(vector_interface(data, "f4"))?;
+                XGBoostError::check_return_value(unsafe {
+                    sys::XGProxyDMatrixSetDataCSR(
+                        self.proxy.handle(),
+                        indptr.as_ptr(),
+                        indices.as_ptr(),
+                        data.as_ptr(),
+                        *num_features as u64,
+                    )
+                })?;
+            }
+        }
+        if let Some(labels) = &batch.labels {
+            self.proxy.write_float_info("label", labels)?;
+        }
+        if let Some(weights) = &batch.weights {
+            self.proxy.write_float_info("weight", weights)?;
+        }
+
+        self.current = Some(batch);
+        Ok(true)
+    }
+}
+
+/// Build an external memory matrix, caching its pages under `cache_prefix`
+///
+/// Returns the matrix handle together with the state the callbacks need, which must
+/// outlive the handle.
+pub(crate) fn create_from_iter(
+    iter: Box<dyn DataIter>,
+    cache_prefix: &Path,
+    missing: f32,
+) -> XGBoostResult<(sys::DMatrixHandle, Box<IterSource>)> {
+    let cache_prefix = cache_prefix
+        .to_str()
+        .ok_or_else(|| XGBoostError::InvalidPath(cache_prefix.to_path_buf()))?;
+    let config = format!(
+        r#"{{"missing": {}, "cache_prefix": "{}", "nthread": 0}}"#,
+        json_f32(missing),
+        json_escape(cache_prefix)
+    );
+    let config_c_str = CString::new(config)?;
+
+    let mut proxy_handle: sys::DMatrixHandle = ptr::null_mut();
+    XGBoostError::check_return_value(unsafe { sys::XGProxyDMatrixCreate(&mut proxy_handle) })?;
+    let mut source = Box::new(IterSource {
+        iter,
+        proxy: DMatrix::from_handle(proxy_handle),
+        current: None,
+        error: None,
+    });
+
+    let mut handle: sys::DMatrixHandle = ptr::null_mut();
+    let result = XGBoostError::check_return_value(unsafe {
+        sys::XGDMatrixCreateFromCallback(
+            &mut *source as *mut IterSource as *mut c_void,
+            proxy_handle,
+            Some(reset_callback),
+            Some(next_callback),
+            config_c_str.as_ptr(),
+            &mut handle,
+        )
+    });
+
+    // A failing iterator looks like the end of the data to XGBoost, so report its error
+    // over whatever XGBoost made of the truncated pass
+    if let Some(error) = source.error.take() {
+        if !handle.is_null() {
+            unsafe { sys::XGDMatrixFree(handle) };
+        }
+        return Err(error);
+    }
+    result?;
+
+    Ok((handle, source))
+}
+
+unsafe extern "C" fn next_callback(handle: sys::DataIterHandle) -> c_int {
+    let source = &mut *(handle as *mut IterSource);
+    match panic::catch_unwind(AssertUnwindSafe(|| source.advance())) {
+        Ok(Ok(true)) => 1,
+        Ok(Ok(false)) => 0,
+        Ok(Err(error)) => {
+            source.error.get_or_insert(error);
+            0
+        }
+        Err(_) => {
+            source.error.get_or_insert(XGBoostError::InvalidInput(
+                "DataIter::next panicked".to_owned(),
+            ));
+            0
+        }
+    }
+}
+
+unsafe extern "C" fn reset_callback(handle: sys::DataIterHandle) {
+    let source = &mut *(handle as *mut IterSource);
+    if panic::catch_unwind(AssertUnwindSafe(|| source.iter.reset())).is_err() {
+        source.error.get_or_insert(XGBoostError::InvalidInput(
+            "DataIter::reset panicked".to_owned(),
+        ));
+    }
+}
+
+/// Build the `__array_interface__` JSON descriptor for a one-dimensional buffer
+fn vector_interface<T>(values: &[T], type_code: &str) -> String {
+    let byte_order = if cfg!(target_endian = "little") {
+        "<"
+    } else {
+        ">"
+    };
+    format!(
+        r#"{{"data": [{}, true], "shape": [{}], "typestr": "{}{}", "version": 3}}"#,
+        values.as_ptr() as usize,
+        values.len(),
+        byte_order,
+        type_code
+    )
+}
+
+/// Escape a string for use inside a JSON string literal
+fn json_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
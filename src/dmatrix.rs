@@ -0,0 +1,1031 @@
+#[cfg(xgboost_external_memory)]
+use crate::data_iter::{create_from_iter, DataIter, IterSource};
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::path_to_c_string;
+use crate::sys;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+
+/// The dimensions of a matrix, in rows and columns
+///
+/// For a [`DMatrix`] the columns are the features. For predictions returned by
+/// [`Booster::predict_with_shape`](crate::Booster::predict_with_shape) there is one row per
+/// input row, and the columns are the values predicted for each row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shape {
+    pub rows: usize,
+    pub cols: usize,
+}
+
+/// An XGBoost data matrix, used for training and evaluation.
+///
+/// The underlying XGBoost handle is freed when the `DMatrix` is dropped.
+pub struct DMatrix {
+    handle: sys::DMatrixHandle,
+    // The iterator of an external memory matrix, which XGBoost calls while training
+    #[cfg(xgboost_external_memory)]
+    _source: Option<Box<IterSource>>,
+}
+
+impl DMatrix {
+    /// Create a matrix from dense data
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `missing` - Value to treat as missing (usually `f32::NAN`)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let dmatrix = DMatrix::from_dense(&data, 2, 2, f32::NAN).unwrap();
+    /// ```
+    pub fn from_dense(
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+    ) -> XGBoostResult<Self> {
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromMat(
+                data.as_ptr(),
+                num_rows as u64,
+                num_features as u64,
+                missing,
+                &mut handle,
+            )
+        })?;
+
+        Ok(DMatrix::from_handle(handle))
+    }
+
+    /// Create a matrix from a 2D `ndarray` array, one row per sample
+    ///
+    /// An array in standard (row-major, contiguous) layout is passed to XGBoost as is.
+    /// Any other layout, such as a transposed or sliced view, is first copied into a new
+    /// row-major buffer, which takes as much memory as the array again while the matrix is
+    /// built; call `.as_standard_layout()` yourself to reuse that copy.
+    ///
+    /// Requires the `ndarray` feature.
+    ///
+    /// # Arguments
+    /// * `arr` - Features, of shape (num_rows, num_features)
+    /// * `missing` - Value to treat as missing (usually `f32::NAN`)
+    ///
+    /// # Example
+    /// ```
+    /// use ndarray::{array, Array2, ShapeBuilder};
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let features = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    /// let mut dtrain = DMatrix::from_array2(&features, f32::NAN).unwrap();
+    /// assert_eq!((dtrain.num_row().unwrap(), dtrain.num_col().unwrap()), (2, 3));
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// // The same values in column-major order are copied into row-major order first
+    /// let column_major =
+    ///     Array2::from_shape_vec((2, 3).f(), vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]).unwrap();
+    /// assert!(!column_major.is_standard_layout());
+    /// let copied = DMatrix::from_array2(&column_major, f32::NAN).unwrap();
+    /// assert_eq!(
+    ///     booster.predict_dmatrix(&copied, 0, false).unwrap(),
+    ///     booster.predict_dmatrix(&dtrain, 0, false).unwrap()
+    /// );
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn from_array2(arr: &ndarray::Array2<f32>, missing: f32) -> XGBoostResult<Self> {
+        let (num_rows, num_features) = arr.dim();
+        match arr.as_slice() {
+            Some(data) => DMatrix::from_dense(data, num_rows, num_features, missing),
+            None => {
+                let standard = arr.as_standard_layout();
+                let data = standard.as_slice().ok_or_else(|| {
+                    XGBoostError::InvalidInput(
+                        "Array could not be copied into row-major layout".to_owned(),
+                    )
+                })?;
+                DMatrix::from_dense(data, num_rows, num_features, missing)
+            }
+        }
+    }
+
+    /// Create a matrix from an Arrow `RecordBatch`, one feature per column, in column order
+    ///
+    /// Integer, float and decimal columns are cast to `f32`, and boolean columns become
+    /// `1.0` or `0.0`. Null entries, and NaN values, are passed to XGBoost as missing values.
+    /// Other column types, such as strings and dictionaries, are rejected with
+    /// [`XGBoostError::InvalidInput`]. The columns are copied into a dense row-major buffer,
+    /// which takes `4 * rows * columns` bytes while the matrix is built.
+    ///
+    /// Requires the `arrow` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int32Array};
+    /// use arrow::record_batch::RecordBatch;
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let batch = RecordBatch::try_from_iter([
+    ///     ("a", Arc::new(Float64Array::from(vec![Some(1.0), None, Some(3.0)])) as ArrayRef),
+    ///     ("b", Arc::new(Int32Array::from(vec![4, 5, 6])) as ArrayRef),
+    ///     ("c", Arc::new(BooleanArray::from(vec![true, false, true])) as ArrayRef),
+    /// ])
+    /// .unwrap();
+    /// let mut dtrain = DMatrix::from_record_batch(&batch).unwrap();
+    /// assert_eq!((dtrain.num_row().unwrap(), dtrain.num_col().unwrap()), (3, 3));
+    /// dtrain.set_labels(&[0.0, 1.0, 1.0]).unwrap();
+    ///
+    /// // The same rows as a dense buffer, with the null as NaN
+    /// let nan = f32::NAN;
+    /// let dense =
+    ///     DMatrix::from_dense(&[1.0, 4.0, 1.0, nan, 5.0, 0.0, 3.0, 6.0, 1.0], 3, 3, nan).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    /// assert_eq!(
+    ///     booster.predict_dmatrix(&dtrain, 0, false).unwrap(),
+    ///     booster.predict_dmatrix(&dense, 0, false).unwrap()
+    /// );
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn from_record_batch(batch: &arrow::record_batch::RecordBatch) -> XGBoostResult<Self> {
+        use arrow::array::{Array, AsArray};
+        use arrow::datatypes::{DataType, Float32Type};
+
+        let num_rows = batch.num_rows();
+        let num_features = batch.num_columns();
+        let mut data = vec![f32::NAN; num_rows * num_features];
+
+        for (col, (field, column)) in batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .enumerate()
+        {
+            if !column.data_type().is_numeric() && column.data_type() != &DataType::Boolean {
+                return Err(XGBoostError::InvalidInput(format!(
+                    "Column '{}' has type {}, only numeric and boolean columns are supported",
+                    field.name(),
+                    column.data_type()
+                )));
+            }
+            let values = arrow::compute::cast(column, &DataType::Float32).map_err(|e| {
+                XGBoostError::Arrow(format!(
+                    "Cannot convert column '{}' to f32: {}",
+                    field.name(),
+                    e
+                ))
+            })?;
+            let values = values.as_primitive::<Float32Type>();
+            for (row, value) in values.iter().enumerate() {
+                if let Some(value) = value {
+                    data[row * num_features + col] = value;
+                }
+            }
+        }
+
+        DMatrix::from_dense(&data, num_rows, num_features, f32::NAN)
+    }
+
+    /// Create a matrix from sparse data in CSC (compressed sparse column) format
+    ///
+    /// The entries of column `j` are `col_ptr[j]..col_ptr[j + 1]` into `row_indices` and
+    /// `data`. Entries that are not stored, and stored entries equal to `missing` (or NaN),
+    /// are treated as missing.
+    ///
+    /// # Arguments
+    /// * `col_ptr` - Offsets of each column's entries, with one more element than columns
+    /// * `row_indices` - Row index of each entry
+    /// * `data` - Value of each entry
+    /// * `num_row` - Number of rows in the matrix
+    /// * `missing` - Value to treat as missing (usually `f32::NAN`)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// // 3 rows, 2 columns: [[1, _], [_, 2], [3, 4]]
+    /// let dmatrix = DMatrix::from_csc(&[0, 2, 4], &[0, 2, 1, 2], &[1.0, 3.0, 2.0, 4.0], 3, f32::NAN)
+    ///     .unwrap();
+    /// ```
+    pub fn from_csc(
+        col_ptr: &[u64],
+        row_indices: &[u32],
+        data: &[f32],
+        num_row: usize,
+        missing: f32,
+    ) -> XGBoostResult<Self> {
+        validate_compressed(Compressed::Csc, col_ptr, row_indices, data.len(), num_row)?;
+
+        // XGBoost only treats NaN entries as missing, so drop entries equal to `missing`
+        let filtered = (!missing.is_nan() && data.contains(&missing))
+            .then(|| drop_csc_value(col_ptr, row_indices, data, missing));
+        let (col_ptr, row_indices, data) = match &filtered {
+            Some((col_ptr, row_indices, data)) => {
+                (col_ptr.as_slice(), row_indices.as_slice(), data.as_slice())
+            }
+            None => (col_ptr, row_indices, data),
+        };
+
+        let col_ptr: Vec<usize> = col_ptr
+            .iter()
+            .map(|&offset| {
+                usize::try_from(offset).map_err(|_| {
+                    XGBoostError::InvalidInput(format!(
+                        "Column offset {} does not fit in usize",
+                        offset
+                    ))
+                })
+            })
+            .collect::<XGBoostResult<_>>()?;
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromCSCEx(
+                col_ptr.as_ptr(),
+                row_indices.as_ptr(),
+                data.as_ptr(),
+                col_ptr.len(),
+                data.len(),
+                num_row,
+                &mut handle,
+            )
+        })?;
+
+        Ok(DMatrix::from_handle(handle))
+    }
+
+    /// Create a matrix from a NumPy-style `__array_interface__` JSON descriptor
+    ///
+    /// A low-level escape hatch for layouts the other constructors do not cover: the
+    /// descriptor is passed to XGBoost's `XGDMatrixCreateFromDense` unchanged, so its
+    /// `strides` can describe strided or transposed views, and its `typestr` any element
+    /// type XGBoost reads, such as `<f8` or `<i4`. XGBoost copies the data into the matrix,
+    /// so the buffer only has to outlive this call.
+    ///
+    /// The descriptor is a JSON object with `data` (`[address, read_only]`), `shape`
+    /// (`[rows, columns]`), `typestr`, `version` (3) and optionally `strides` in bytes,
+    /// which default to C order. Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `array_interface` - JSON descriptor of the data
+    /// * `config` - JSON object with `missing`, the value to treat as missing (`NaN` is
+    ///   accepted), and optionally `nthread`, e.g. `{"missing": NaN, "nthread": 0}`
+    ///
+    /// # Safety
+    /// XGBoost reads memory at the address in the descriptor without any check. For the
+    /// duration of the call, that address must point to a live allocation containing every
+    /// element the shape, strides and `typestr` describe, correctly aligned for the type.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// // Every other row of a 4 x 2 buffer, read through a row stride of 4 floats
+    /// let buffer: [f32; 8] = [1.0, 2.0, -1.0, -1.0, 3.0, 4.0, -1.0, -1.0];
+    /// let array_interface = format!(
+    ///     r#"{{"data": [{}, true], "shape": [2, 2], "strides": [16, 4], "typestr": "<f4", "version": 3}}"#,
+    ///     buffer.as_ptr() as usize
+    /// );
+    /// // The descriptor addresses elements 0, 1, 4 and 5 of `buffer`, which outlives the call
+    /// let strided = unsafe {
+    ///     DMatrix::from_array_interface(&array_interface, r#"{"missing": NaN}"#).unwrap()
+    /// };
+    ///
+    /// let contiguous = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// assert_eq!(
+    ///     booster.predict_dmatrix(&strided, 0, false).unwrap(),
+    ///     booster.predict_dmatrix(&contiguous, 0, false).unwrap()
+    /// );
+    /// ```
+    #[cfg(xgboost_inplace_predict)]
+    pub unsafe fn from_array_interface(array_interface: &str, config: &str) -> XGBoostResult<Self> {
+        let array_interface_c_str = CString::new(array_interface)?;
+        let config_c_str = CString::new(config)?;
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(sys::XGDMatrixCreateFromDense(
+            array_interface_c_str.as_ptr(),
+            config_c_str.as_ptr(),
+            &mut handle,
+        ))?;
+
+        Ok(DMatrix::from_handle(handle))
+    }
+
+    /// Create an external memory matrix from batches produced by an iterator
+    ///
+    /// XGBoost pulls the batches one at a time and writes them to page files under
+    /// `cache_prefix`, so the whole dataset never has to be in memory at once. The matrix
+    /// keeps the iterator and calls it again while training, so every pass must produce
+    /// the same batches. Labels and weights are taken from the batches.
+    ///
+    /// # Arguments
+    /// * `iter` - Source of the batches
+    /// * `cache_prefix` - Path prefix of the page files XGBoost writes
+    /// * `missing` - Value to treat as missing (usually `f32::NAN`)
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{Batch, BoosterBuilder, DMatrix, DataIter, XGBoostResult};
+    ///
+    /// struct Batches {
+    ///     next: usize,
+    /// }
+    ///
+    /// impl DataIter for Batches {
+    ///     fn next(&mut self) -> Option<XGBoostResult<Batch>> {
+    ///         if self.next == 3 {
+    ///             return None;
+    ///         }
+    ///         let start = self.next as f32 * 2.0;
+    ///         self.next += 1;
+    ///         // 2 rows of 2 features per batch
+    ///         let data = vec![start, 1.0, start + 1.0, 0.0];
+    ///         Some(Batch::dense(data, 2, 2).and_then(|batch| batch.with_labels(vec![0.0, 1.0])))
+    ///     }
+    ///
+    ///     fn reset(&mut self) {
+    ///         self.next = 0;
+    ///     }
+    /// }
+    ///
+    /// let cache_dir = std::env::temp_dir().join("xgboost_rust_from_iter");
+    /// std::fs::create_dir_all(&cache_dir).unwrap();
+    /// let dtrain = DMatrix::from_iter(Batches { next: 0 }, cache_dir.join("cache"), f32::NAN)
+    ///     .unwrap();
+    /// assert_eq!(dtrain.num_row().unwrap(), 6);
+    ///
+    /// let booster = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    /// assert_eq!(booster.num_boosted_rounds().unwrap(), 2);
+    /// # drop(dtrain);
+    /// # std::fs::remove_dir_all(&cache_dir).unwrap();
+    /// ```
+    #[cfg(xgboost_external_memory)]
+    pub fn from_iter<I: DataIter + 'static, P: AsRef<Path>>(
+        iter: I,
+        cache_prefix: P,
+        missing: f32,
+    ) -> XGBoostResult<Self> {
+        let (handle, source) = create_from_iter(Box::new(iter), cache_prefix.as_ref(), missing)?;
+        Ok(DMatrix {
+            handle,
+            _source: Some(source),
+        })
+    }
+
+    /// Load a matrix from a file in libsvm or CSV format
+    ///
+    /// XGBoost's URI syntax can be used to select the format and label column,
+    /// e.g. `data.csv?format=csv&label_column=0`.
+    ///
+    /// # Arguments
+    /// * `path` - Path or URI of the data file
+    /// * `silent` - Whether to suppress XGBoost's loading messages
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_file("train.csv?format=csv&label_column=0", true).unwrap();
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P, silent: bool) -> XGBoostResult<Self> {
+        let path_c_str = path_to_c_string(path.as_ref())?;
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixCreateFromFile(path_c_str.as_ptr(), silent as i32, &mut handle)
+        })?;
+
+        Ok(DMatrix::from_handle(handle))
+    }
+
+    /// Save the matrix, with its labels and other info, in XGBoost's binary format
+    ///
+    /// Loading the saved file with [`DMatrix::from_file`] is much faster than parsing the
+    /// original text data again, so it can be used to cache a preprocessed matrix.
+    ///
+    /// # Arguments
+    /// * `path` - Path where to save the matrix, conventionally with a `.buffer` extension
+    /// * `silent` - Whether to suppress XGBoost's saving messages
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_file("train.csv?format=csv&label_column=0", true).unwrap();
+    /// dmatrix.save_binary("train.buffer", true).unwrap();
+    /// let cached = DMatrix::from_file("train.buffer", true).unwrap();
+    /// ```
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P, silent: bool) -> XGBoostResult<()> {
+        let path_c_str = path_to_c_string(path.as_ref())?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSaveBinary(self.handle, path_c_str.as_ptr(), silent as i32)
+        })
+    }
+
+    /// Create a new matrix containing only the given rows of this one
+    ///
+    /// Labels, weights and other row information are sliced along with the data.
+    ///
+    /// # Arguments
+    /// * `row_indices` - Indices of the rows to keep, in `0..num_row()`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_file("data.libsvm?format=libsvm", true).unwrap();
+    /// let num_rows = dmatrix.num_row().unwrap() as i32;
+    /// let split = num_rows * 8 / 10;
+    ///
+    /// let train_rows: Vec<i32> = (0..split).collect();
+    /// let valid_rows: Vec<i32> = (split..num_rows).collect();
+    /// let dtrain = dmatrix.slice(&train_rows).unwrap();
+    /// let dvalid = dmatrix.slice(&valid_rows).unwrap();
+    /// ```
+    pub fn slice(&self, row_indices: &[i32]) -> XGBoostResult<DMatrix> {
+        let num_rows = self.num_row()?;
+        if let Some(&index) = row_indices
+            .iter()
+            .find(|&&index| index < 0 || index as usize >= num_rows)
+        {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Row index {} out of range for matrix with {} rows",
+                index, num_rows
+            )));
+        }
+
+        let mut handle: sys::DMatrixHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSliceDMatrix(
+                self.handle,
+                row_indices.as_ptr(),
+                row_indices.len() as u64,
+                &mut handle,
+            )
+        })?;
+
+        Ok(DMatrix::from_handle(handle))
+    }
+
+    /// Get the number of rows in the matrix
+    pub fn num_row(&self) -> XGBoostResult<usize> {
+        let mut out: u64 = 0;
+        XGBoostError::check_return_value(unsafe { sys::XGDMatrixNumRow(self.handle, &mut out) })?;
+        Ok(out as usize)
+    }
+
+    /// Get the number of columns in the matrix
+    pub fn num_col(&self) -> XGBoostResult<usize> {
+        let mut out: u64 = 0;
+        XGBoostError::check_return_value(unsafe { sys::XGDMatrixNumCol(self.handle, &mut out) })?;
+        Ok(out as usize)
+    }
+
+    /// Get the number of entries stored in the matrix, i.e. the values that are not missing
+    #[cfg(xgboost_num_nonmissing)]
+    pub fn num_nonmissing(&self) -> XGBoostResult<u64> {
+        let mut out: u64 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixNumNonMissing(self.handle, &mut out)
+        })?;
+        Ok(out)
+    }
+
+    /// Get the fraction of the matrix's cells that are not missing
+    ///
+    /// # Returns
+    /// [`DMatrix::num_nonmissing`] divided by `num_row * num_col`, or 0 for a matrix
+    /// without rows or columns
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_dense(&[1.0, f32::NAN, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// assert_eq!(dmatrix.num_nonmissing().unwrap(), 3);
+    /// assert_eq!(dmatrix.density().unwrap(), 0.75);
+    /// ```
+    #[cfg(xgboost_num_nonmissing)]
+    pub fn density(&self) -> XGBoostResult<f64> {
+        let shape = self.shape()?;
+        let cells = shape.rows as f64 * shape.cols as f64;
+        if cells == 0.0 {
+            return Ok(0.0);
+        }
+        Ok(self.num_nonmissing()? as f64 / cells)
+    }
+
+    /// Read the contents of the matrix back in CSR (compressed sparse row) format
+    ///
+    /// Missing values are not stored, so only the present entries of each row appear.
+    ///
+    /// # Returns
+    /// `(indptr, indices, data)`, where the entries of row `i` are
+    /// `indptr[i]..indptr[i + 1]` into `indices` (column indices) and `data` (values)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let dmatrix = DMatrix::from_dense(&[1.0, f32::NAN, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// let (indptr, indices, data) = dmatrix.as_csr().unwrap();
+    /// assert_eq!(indptr, vec![0, 1, 3]);
+    /// assert_eq!(indices, vec![0, 0, 1]);
+    /// assert_eq!(data, vec![1.0, 3.0, 4.0]);
+    /// ```
+    #[cfg(xgboost_dmatrix_csr)]
+    pub fn as_csr(&self) -> XGBoostResult<(Vec<u64>, Vec<u32>, Vec<f32>)> {
+        let num_rows = self.num_row()?;
+
+        let mut num_non_missing: u64 = 0;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixNumNonMissing(self.handle, &mut num_non_missing)
+        })?;
+
+        let mut indptr = vec![0u64; num_rows + 1];
+        let mut indices = vec![0u32; num_non_missing as usize];
+        let mut data = vec![0f32; num_non_missing as usize];
+
+        let config = CString::new("{}")?;
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetDataAsCSR(
+                self.handle,
+                config.as_ptr(),
+                indptr.as_mut_ptr(),
+                indices.as_mut_ptr(),
+                data.as_mut_ptr(),
+            )
+        })?;
+
+        Ok((indptr, indices, data))
+    }
+
+    /// Check whether two matrices hold the same values, within a tolerance
+    ///
+    /// Meant for testing conversion code against a hand-built expected matrix. The
+    /// matrices are equal when they have the same [`shape`](DMatrix::shape), the same
+    /// entries present in each row, and values that differ by at most `tol`. A missing
+    /// entry is not the same as a stored zero, and two stored NaN values are equal.
+    /// Labels, weights and other info fields are not compared.
+    ///
+    /// Requires XGBoost ≥ 1.7, as it reads both matrices with [`DMatrix::as_csr`].
+    ///
+    /// # Arguments
+    /// * `other` - Matrix to compare against
+    /// * `tol` - Largest allowed absolute difference between two values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let expected = DMatrix::from_dense(&[1.0, f32::NAN, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// let converted = DMatrix::from_dense(&[1.0, -1.0, 3.0, 4.000001], 2, 2, -1.0).unwrap();
+    /// assert!(converted.approx_eq(&expected, 1e-5).unwrap());
+    /// assert!(!converted.approx_eq(&expected, 0.0).unwrap());
+    /// ```
+    #[cfg(xgboost_dmatrix_csr)]
+    pub fn approx_eq(&self, other: &DMatrix, tol: f32) -> XGBoostResult<bool> {
+        if self.shape()? != other.shape()? {
+            return Ok(false);
+        }
+
+        let (indptr, indices, data) = self.as_csr()?;
+        let (other_indptr, other_indices, other_data) = other.as_csr()?;
+        if indptr != other_indptr || indices != other_indices {
+            return Ok(false);
+        }
+
+        Ok(data
+            .iter()
+            .zip(&other_data)
+            .all(|(a, b)| (a.is_nan() && b.is_nan()) || (a - b).abs() <= tol))
+    }
+
+    /// Get the number of rows and columns in the matrix
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{DMatrix, Shape};
+    ///
+    /// let dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3, 2, f32::NAN).unwrap();
+    /// let sliced = dmatrix.slice(&[0, 2]).unwrap();
+    /// assert_eq!(sliced.shape().unwrap(), Shape { rows: 2, cols: 2 });
+    /// ```
+    pub fn shape(&self) -> XGBoostResult<Shape> {
+        Ok(Shape {
+            rows: self.num_row()?,
+            cols: self.num_col()?,
+        })
+    }
+
+    /// Set the labels (training targets) of the matrix
+    ///
+    /// # Arguments
+    /// * `labels` - One label per row
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let mut dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dmatrix.set_labels(&[0.0, 1.0]).unwrap();
+    /// ```
+    pub fn set_labels(&mut self, labels: &[f32]) -> XGBoostResult<()> {
+        self.set_float_info("label", labels)
+    }
+
+    /// Set the sample weights of the matrix
+    ///
+    /// # Arguments
+    /// * `weights` - One weight per row
+    pub fn set_weights(&mut self, weights: &[f32]) -> XGBoostResult<()> {
+        self.set_float_info("weight", weights)
+    }
+
+    /// Set the base margin of the matrix, the starting score boosting adds to
+    ///
+    /// Predictions with [`Booster::predict_dmatrix`](crate::Booster::predict_dmatrix), and
+    /// training on this matrix, start from these margins instead of the model's
+    /// `base_score`, e.g. to stack a model on top of another model's output margins.
+    /// Dense predictions with [`Booster::predict`](crate::Booster::predict) always start
+    /// from `base_score`.
+    ///
+    /// Set the margin before the first prediction on this matrix: XGBoost caches the
+    /// predictions of each matrix, and changing the margin afterwards does not refresh them.
+    ///
+    /// # Arguments
+    /// * `margin` - One margin per row and model output (row-major), e.g. one per class for
+    ///   a multiclass model. Use
+    ///   [`Booster::check_base_margin`](crate::Booster::check_base_margin) to check the
+    ///   number of outputs against the model.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let mut dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dmatrix.set_base_margin(&[0.3, -1.2]).unwrap(); // margins from a first-stage model
+    /// let predictions = booster.predict_dmatrix(&dmatrix, 0, false).unwrap();
+    /// ```
+    pub fn set_base_margin(&mut self, margin: &[f32]) -> XGBoostResult<()> {
+        let num_rows = self.num_row()?;
+        let is_multiple = match margin.len().checked_rem(num_rows) {
+            Some(remainder) => remainder == 0 && !margin.is_empty(),
+            None => margin.is_empty(),
+        };
+        if !is_multiple {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Base margin has {} values, expected a non-zero multiple of the {} rows",
+                margin.len(),
+                num_rows
+            )));
+        }
+
+        self.write_float_info("base_margin", margin)
+    }
+
+    /// Set the query groups of the matrix, for ranking objectives such as `rank:ndcg`
+    ///
+    /// The rows of each group must be contiguous: the first `group[0]` rows form the first
+    /// query, the next `group[1]` rows the second, and so on.
+    ///
+    /// # Arguments
+    /// * `group` - Number of rows in each group, summing to the number of rows
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+    /// let mut dmatrix = DMatrix::from_dense(&data, 5, 2, f32::NAN).unwrap();
+    /// dmatrix.set_labels(&[2.0, 1.0, 0.0, 1.0, 0.0]).unwrap();
+    /// dmatrix.set_group(&[3, 2]).unwrap(); // rows 0-2 and rows 3-4
+    /// ```
+    pub fn set_group(&mut self, group: &[u32]) -> XGBoostResult<()> {
+        let num_rows = self.num_row()?;
+        let group_rows: u64 = group.iter().map(|&size| size as u64).sum();
+        if group_rows != num_rows as u64 {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Group sizes sum to {} but the matrix has {} rows",
+                group_rows, num_rows
+            )));
+        }
+
+        let field_c_str = CString::new("group")?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetUIntInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                group.as_ptr(),
+                group.len() as u64,
+            )
+        })
+    }
+
+    /// Get a float info field of the matrix (e.g. `label` or `weight`)
+    ///
+    /// # Returns
+    /// The field values, or an empty vector if the field has not been set
+    pub fn get_float_info(&self, field: &str) -> XGBoostResult<Vec<f32>> {
+        let field_c_str = CString::new(field)?;
+
+        let mut out_len: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixGetFloatInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                &mut out_len,
+                &mut out_result,
+            )
+        })?;
+
+        if out_len == 0 || out_result.is_null() {
+            return Ok(Vec::new());
+        }
+
+        let values = unsafe { std::slice::from_raw_parts(out_result, out_len as usize) };
+        Ok(values.to_vec())
+    }
+
+    fn set_float_info(&mut self, field: &str, values: &[f32]) -> XGBoostResult<()> {
+        let num_rows = self.num_row()?;
+        if values.len() != num_rows {
+            return Err(XGBoostError::DimensionMismatch {
+                expected: num_rows,
+                got: values.len(),
+            });
+        }
+
+        self.write_float_info(field, values)
+    }
+
+    pub(crate) fn write_float_info(&mut self, field: &str, values: &[f32]) -> XGBoostResult<()> {
+        let field_c_str = CString::new(field)?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetFloatInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                values.as_ptr(),
+                values.len() as u64,
+            )
+        })
+    }
+
+    /// Set the feature names of the matrix
+    ///
+    /// # Arguments
+    /// * `names` - One name per column
+    pub fn set_feature_names(&mut self, names: &[&str]) -> XGBoostResult<()> {
+        self.set_str_feature_info("feature_name", names)
+    }
+
+    /// Set the feature types of the matrix
+    ///
+    /// Use `q` for numerical features and `c` for categorical features. Categorical
+    /// features also require the booster to be trained with `enable_categorical`.
+    ///
+    /// # Arguments
+    /// * `types` - One type per column
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::DMatrix;
+    ///
+    /// let mut dmatrix = DMatrix::from_dense(&[1.0, 0.0, 3.0, 2.0], 2, 2, f32::NAN).unwrap();
+    /// dmatrix.set_feature_types(&["q", "c"]).unwrap();
+    /// ```
+    pub fn set_feature_types(&mut self, types: &[&str]) -> XGBoostResult<()> {
+        self.set_str_feature_info("feature_type", types)
+    }
+
+    fn set_str_feature_info(&mut self, field: &str, values: &[&str]) -> XGBoostResult<()> {
+        let num_cols = self.num_col()?;
+        if values.len() != num_cols {
+            return Err(XGBoostError::DimensionMismatch {
+                expected: num_cols,
+                got: values.len(),
+            });
+        }
+
+        let field_c_str = CString::new(field)?;
+        let value_c_strs = values
+            .iter()
+            .map(|value| CString::new(*value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut value_ptrs: Vec<*const c_char> =
+            value_c_strs.iter().map(|value| value.as_ptr()).collect();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGDMatrixSetStrFeatureInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                value_ptrs.as_mut_ptr(),
+                value_ptrs.len() as u64,
+            )
+        })
+    }
+
+    pub(crate) fn from_handle(handle: sys::DMatrixHandle) -> Self {
+        DMatrix {
+            handle,
+            #[cfg(xgboost_external_memory)]
+            _source: None,
+        }
+    }
+
+    pub(crate) fn handle(&self) -> sys::DMatrixHandle {
+        self.handle
+    }
+}
+
+impl Drop for DMatrix {
+    fn drop(&mut self) {
+        unsafe {
+            sys::XGDMatrixFree(self.handle);
+        }
+    }
+}
+
+/// Copy CSC data without the entries equal to `value`
+fn drop_csc_value(
+    col_ptr: &[u64],
+    row_indices: &[u32],
+    data: &[f32],
+    value: f32,
+) -> (Vec<u64>, Vec<u32>, Vec<f32>) {
+    let mut kept_col_ptr = Vec::with_capacity(col_ptr.len());
+    let mut kept_row_indices = Vec::with_capacity(row_indices.len());
+    let mut kept_data = Vec::with_capacity(data.len());
+
+    kept_col_ptr.push(0);
+    for bounds in col_ptr.windows(2) {
+        for entry in bounds[0] as usize..bounds[1] as usize {
+            if data[entry] != value {
+                kept_row_indices.push(row_indices[entry]);
+                kept_data.push(data[entry]);
+            }
+        }
+        kept_col_ptr.push(kept_data.len() as u64);
+    }
+
+    (kept_col_ptr, kept_row_indices, kept_data)
+}
+
+/// Layout of a compressed sparse matrix, naming its parts in validation errors
+#[derive(Clone, Copy)]
+pub(crate) enum Compressed {
+    #[cfg_attr(not(xgboost_external_memory), allow(dead_code))]
+    Csr,
+    Csc,
+}
+
+impl Compressed {
+    /// Format, offsets argument, indices argument, what an offset spans and what is indexed
+    fn names(
+        self,
+    ) -> (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+    ) {
+        match self {
+            Compressed::Csr => ("CSR", "indptr", "indices", "row", "features"),
+            Compressed::Csc => ("CSC", "col_ptr", "row_indices", "column", "rows"),
+        }
+    }
+}
+
+/// Check the offsets and indices of a CSR or CSC matrix before passing them to XGBoost
+///
+/// Errors name the first position that breaks an invariant, e.g.
+/// `CSR indptr[42]=1000 exceeds data length 800`, so the caller can find the bad entry.
+///
+/// # Arguments
+/// * `layout` - Whether `offsets` are row (CSR) or column (CSC) offsets
+/// * `offsets` - Offsets of each row or column's entries, with one more element than those
+/// * `indices` - Column (CSR) or row (CSC) index of each entry
+/// * `data_len` - Number of entries
+/// * `index_bound` - Number of columns (CSR) or rows (CSC), which every index must be below
+pub(crate) fn validate_compressed(
+    layout: Compressed,
+    offsets: &[u64],
+    indices: &[u32],
+    data_len: usize,
+    index_bound: usize,
+) -> XGBoostResult<()> {
+    let (format, offsets_name, indices_name, span, indexed) = layout.names();
+
+    if indices.len() != data_len {
+        return Err(XGBoostError::DimensionMismatch {
+            expected: data_len,
+            got: indices.len(),
+        });
+    }
+    match offsets.first() {
+        None => {
+            return Err(XGBoostError::InvalidInput(format!(
+                "{} {} is empty, it needs at least the leading 0",
+                format, offsets_name
+            )))
+        }
+        Some(&first) if first != 0 => {
+            return Err(XGBoostError::InvalidInput(format!(
+                "{} {}[0]={} must be 0",
+                format, offsets_name, first
+            )))
+        }
+        Some(_) => {}
+    }
+    for (i, pair) in offsets.windows(2).enumerate() {
+        if pair[1] < pair[0] {
+            return Err(XGBoostError::InvalidInput(format!(
+                "{} {}[{}]={} is less than {}[{}]={}, offsets must be non-decreasing",
+                format,
+                offsets_name,
+                i + 1,
+                pair[1],
+                offsets_name,
+                i,
+                pair[0]
+            )));
+        }
+        if pair[1] > data_len as u64 {
+            return Err(XGBoostError::InvalidInput(format!(
+                "{} {}[{}]={} exceeds data length {}",
+                format,
+                offsets_name,
+                i + 1,
+                pair[1],
+                data_len
+            )));
+        }
+    }
+    let last = offsets.len() - 1;
+    if offsets[last] != data_len as u64 {
+        return Err(XGBoostError::InvalidInput(format!(
+            "{} {}[{}]={} is the last offset but data has {} entries",
+            format, offsets_name, last, offsets[last], data_len
+        )));
+    }
+    if let Some((position, &index)) = indices
+        .iter()
+        .enumerate()
+        .find(|(_, &index)| index as usize >= index_bound)
+    {
+        // Offsets are non-decreasing and start at 0, so this finds the entry's row or column
+        let owner = offsets.partition_point(|&offset| offset <= position as u64) - 1;
+        return Err(XGBoostError::InvalidInput(format!(
+            "{} {}[{}]={} in {} {} is out of range for a matrix with {} {}",
+            format, indices_name, position, index, span, owner, index_bound, indexed
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check that a dense row-major buffer has exactly `num_rows * num_features` elements
+pub(crate) fn validate_dense_shape(
+    data_len: usize,
+    num_rows: usize,
+    num_features: usize,
+) -> XGBoostResult<()> {
+    let expected_len = num_rows.checked_mul(num_features).ok_or_else(|| {
+        XGBoostError::InvalidInput(format!(
+            "Integer overflow: num_rows ({}) * num_features ({}) exceeds usize::MAX",
+            num_rows, num_features
+        ))
+    })?;
+
+    if data_len != expected_len {
+        return Err(XGBoostError::DimensionMismatch {
+            expected: expected_len,
+            got: data_len,
+        });
+    }
+
+    Ok(())
+}
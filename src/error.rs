@@ -1,17 +1,74 @@
 use crate::sys;
-use std::ffi::CStr;
+use std::ffi::{CStr, NulError};
 use std::fmt;
+use std::path::PathBuf;
+use std::str::Utf8Error;
 
 pub type XGBoostResult<T> = std::result::Result<T, XGBoostError>;
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct XGBoostError {
-    pub description: String,
+/// Errors returned by this crate
+///
+/// XGBoost keeps its last error message per thread, so the message is copied into the
+/// error on the thread where the call failed. Errors own their data and are `Send + Sync`,
+/// so they can be returned from other threads, e.g. through `tokio::task::spawn_blocking`.
+///
+/// Some variants only exist with a cargo feature, such as `Polars`, `Arrow` and `Http`, and new
+/// variants may be added, so a `match` on the error needs a wildcard arm.
+///
+/// # Example
+/// ```
+/// use xgboost_rust::{Booster, XGBoostError};
+///
+/// fn assert_send_sync<T: Send + Sync>() {}
+/// assert_send_sync::<XGBoostError>();
+///
+/// let handle = std::thread::spawn(|| Booster::load_from_buffer(b"not a model").map(drop));
+/// match handle.join().unwrap() {
+///     Err(XGBoostError::Native(message)) => assert_ne!(message, "Unknown error"),
+///     other => panic!("expected an error from XGBoost, got {:?}", other),
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum XGBoostError {
+    /// A file that was passed in does not exist
+    FileNotFound(PathBuf),
+    /// A path could not be passed to XGBoost because it is not valid UTF-8
+    InvalidPath(PathBuf),
+    /// An input buffer does not have the number of elements its dimensions imply
+    DimensionMismatch { expected: usize, got: usize },
+    /// A string could not be passed to XGBoost because it contains a NUL byte
+    NulByte(String),
+    /// A string returned by XGBoost is not valid UTF-8
+    InvalidUtf8(String),
+    /// An argument was rejected before reaching XGBoost
+    InvalidInput(String),
+    /// A filesystem operation performed by this crate failed
+    Io(String),
+    /// A Polars operation failed
+    #[cfg(feature = "polars")]
+    Polars(String),
+    /// An Arrow operation failed
+    #[cfg(feature = "arrow")]
+    Arrow(String),
+    /// Downloading a model failed, or the server did not return it
+    #[cfg(feature = "remote")]
+    Http(String),
+    /// The linked XGBoost library is not the version the bindings were generated for
+    VersionMismatch {
+        compiled: (i32, i32, i32),
+        linked: (i32, i32, i32),
+    },
+    /// An error reported by XGBoost itself, usually the output of `XGBGetLastError`
+    Native(String),
 }
 
 impl XGBoostError {
     /// Check the return value from an XGBoost FFI call, and return the last error message on error.
     /// Return values of 0 are treated as success, non-zero values are treated as errors.
+    ///
+    /// Must be called on the thread that made the call, since that is where XGBoost keeps
+    /// the message.
     pub fn check_return_value(ret_val: i32) -> XGBoostResult<()> {
         if ret_val == 0 {
             Ok(())
@@ -21,19 +78,72 @@ impl XGBoostError {
     }
 
     /// Fetch current error message from XGBoost.
+    ///
+    /// Called right after the failing call, since the message is overwritten by the next
+    /// failing call on the same thread.
     fn fetch_xgboost_error() -> Self {
-        let c_str = unsafe { CStr::from_ptr(sys::XGBGetLastError()) };
-        let str_slice = c_str.to_str().unwrap_or("Unknown error");
-        XGBoostError {
-            description: str_slice.to_owned(),
-        }
+        XGBoostError::Native(last_error_message())
+    }
+}
+
+/// Read XGBoost's last error message on the current thread
+///
+/// Unlike other strings from XGBoost, invalid UTF-8 is replaced rather than reported, so
+/// that the original error is never lost.
+pub(crate) fn last_error_message() -> String {
+    let message = unsafe { sys::XGBGetLastError() };
+    if message.is_null() {
+        return "Unknown error".to_owned();
     }
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
 }
 
 impl fmt::Display for XGBoostError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.description)
+        match self {
+            XGBoostError::FileNotFound(path) => write!(f, "File not found: {}", path.display()),
+            XGBoostError::InvalidPath(path) => write!(
+                f,
+                "Path contains invalid UTF-8 characters: {}",
+                path.display()
+            ),
+            XGBoostError::DimensionMismatch { expected, got } => write!(
+                f,
+                "Data length mismatch: expected {} elements, got {}",
+                expected, got
+            ),
+            XGBoostError::NulByte(description)
+            | XGBoostError::InvalidUtf8(description)
+            | XGBoostError::InvalidInput(description)
+            | XGBoostError::Io(description)
+            | XGBoostError::Native(description) => write!(f, "{}", description),
+            #[cfg(feature = "polars")]
+            XGBoostError::Polars(description) => write!(f, "Polars error: {}", description),
+            #[cfg(feature = "arrow")]
+            XGBoostError::Arrow(description) => write!(f, "Arrow error: {}", description),
+            #[cfg(feature = "remote")]
+            XGBoostError::Http(description) => write!(f, "{}", description),
+            XGBoostError::VersionMismatch { compiled, linked } => write!(
+                f,
+                "XGBoost version mismatch: built against {}.{}.{}, but linked library is {}.{}.{}",
+                compiled.0, compiled.1, compiled.2, linked.0, linked.1, linked.2
+            ),
+        }
     }
 }
 
 impl std::error::Error for XGBoostError {}
+
+impl From<NulError> for XGBoostError {
+    fn from(e: NulError) -> Self {
+        XGBoostError::NulByte(format!("String contains NUL byte: {}", e))
+    }
+}
+
+impl From<Utf8Error> for XGBoostError {
+    fn from(e: Utf8Error) -> Self {
+        XGBoostError::InvalidUtf8(format!("String contains invalid UTF-8: {}", e))
+    }
+}
@@ -0,0 +1,266 @@
+use crate::sys;
+
+/// Get the version of the linked XGBoost library
+///
+/// # Returns
+/// A `(major, minor, patch)` tuple
+///
+/// # Example
+/// ```no_run
+/// let (major, minor, patch) = xgboost_rust::xgboost_version();
+/// println!("Linked against XGBoost {}.{}.{}", major, minor, patch);
+/// ```
+pub fn xgboost_version() -> (i32, i32, i32) {
+    let mut major = 0;
+    let mut minor = 0;
+    let mut patch = 0;
+
+    unsafe {
+        sys::XGBoostVersion(&mut major, &mut minor, &mut patch);
+    }
+
+    (major, minor, patch)
+}
+
+/// Get the XGBoost version whose headers this crate was built against
+///
+/// This is `XGBOOST_VERSION` at build time, or the version of the library found by
+/// pkg-config.
+///
+/// # Returns
+/// A `(major, minor, patch)` tuple
+pub fn xgboost_compiled_version() -> (i32, i32, i32) {
+    let mut parts = env!("XGBOOST_RUST_HEADER_VERSION")
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// Check that the linked XGBoost library matches the headers this crate was built against
+///
+/// A library of a different major or minor version, e.g. one picked up from
+/// `LD_LIBRARY_PATH` or a different `XGBOOST_LIB_DIR`, may not match the generated
+/// bindings and can crash. Patch releases are compatible.
+///
+/// The check also runs once when the first [`Booster`](crate::Booster) is created:
+/// a mismatch is printed as a warning to stderr, or returned as an error from the
+/// constructor when the `XGBOOST_STRICT_VERSION_CHECK` environment variable is set.
+///
+/// # Example
+/// ```no_run
+/// xgboost_rust::check_xgboost_version().expect("incompatible XGBoost library");
+/// ```
+pub fn check_xgboost_version() -> crate::XGBoostResult<()> {
+    let compiled = xgboost_compiled_version();
+    let linked = xgboost_version();
+    if (compiled.0, compiled.1) == (linked.0, linked.1) {
+        Ok(())
+    } else {
+        Err(crate::XGBoostError::VersionMismatch { compiled, linked })
+    }
+}
+
+/// Run [`check_xgboost_version`] once per process, warning instead of failing unless
+/// `XGBOOST_STRICT_VERSION_CHECK` is set
+pub(crate) fn check_xgboost_version_once() -> crate::XGBoostResult<()> {
+    use std::sync::OnceLock;
+
+    static RESULT: OnceLock<crate::XGBoostResult<()>> = OnceLock::new();
+    RESULT
+        .get_or_init(|| {
+            let strict = std::env::var("XGBOOST_STRICT_VERSION_CHECK")
+                .is_ok_and(|value| !value.is_empty() && value != "0");
+            match check_xgboost_version() {
+                Err(e) if !strict => {
+                    eprintln!("warning: {}", e);
+                    Ok(())
+                }
+                result => result,
+            }
+        })
+        .clone()
+}
+
+/// Get the message of the last error XGBoost reported on the current thread
+///
+/// Errors returned by this crate already carry this message as [`XGBoostError::Native`],
+/// read immediately after the failing call. This is only needed for logging in cases
+/// the crate does not cover, such as errors from XGBoost calls made outside it.
+///
+/// [`XGBoostError::Native`]: crate::XGBoostError::Native
+///
+/// # Example
+/// ```no_run
+/// println!("last XGBoost error: {}", xgboost_rust::xgboost_last_error());
+/// ```
+pub fn xgboost_last_error() -> String {
+    crate::error::last_error_message()
+}
+
+/// Get the build configuration of the linked XGBoost library
+///
+/// Requires XGBoost ≥ 1.5.
+///
+/// # Returns
+/// A JSON string describing how the library was built (CUDA, OpenMP, compiler, ...)
+///
+/// # Example
+/// ```no_run
+/// let build_info = xgboost_rust::xgboost_build_info().unwrap();
+/// println!("{}", build_info);
+/// ```
+#[cfg(xgboost_build_info)]
+pub fn xgboost_build_info() -> crate::XGBoostResult<String> {
+    use crate::XGBoostError;
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    let mut out: *const c_char = ptr::null();
+
+    XGBoostError::check_return_value(unsafe { sys::XGBuildInfo(&mut out) })?;
+
+    unsafe { crate::model::cstr_to_string(out, "build info") }
+}
+
+/// Check whether the linked XGBoost library was built with CUDA support
+///
+/// Requires XGBoost ≥ 1.5.
+///
+/// # Example
+/// ```no_run
+/// if !xgboost_rust::xgboost_has_cuda().unwrap() {
+///     println!("GPU prediction is not available, using the CPU");
+/// }
+/// ```
+#[cfg(xgboost_build_info)]
+pub fn xgboost_has_cuda() -> crate::XGBoostResult<bool> {
+    let build_info = xgboost_build_info()?;
+    let use_cuda = crate::json::lookup(&build_info, &["USE_CUDA"])?;
+    Ok(use_cuda.as_deref() == Some("true"))
+}
+
+/// Set XGBoost's global configuration for the calling thread
+///
+/// The configuration is not tied to one booster: it applies to every booster used on
+/// the calling thread. XGBoost keeps it per thread, though, so it does not reach other
+/// threads, which start from XGBoost's defaults. Only the given keys change, e.g.
+/// `verbosity` (0 silent, 1 warning, 2 info, 3 debug) or `use_rmm`.
+///
+/// Call it on every thread that trains or predicts. This includes the Tokio blocking
+/// threads [`Booster::predict_async`](crate::Booster::predict_async) runs on, which are
+/// easiest to reach with `tokio::runtime::Builder::on_thread_start`.
+///
+/// An `nthread` key is checked to be a non-negative integer before it is passed on, and
+/// versions of XGBoost without a global `nthread` report it as an unsupported setting
+/// (see [`set_num_threads`]).
+///
+/// # Arguments
+/// * `json` - JSON object of settings to change
+///
+/// # Example
+/// ```no_run
+/// // Silence XGBoost's log messages on this thread
+/// xgboost_rust::set_global_config(r#"{"verbosity": 0}"#).unwrap();
+///
+/// // And on every thread of a Tokio runtime, including its blocking threads
+/// # #[cfg(feature = "tokio")]
+/// let runtime = tokio::runtime::Builder::new_current_thread()
+///     .on_thread_start(|| xgboost_rust::set_global_config(r#"{"verbosity": 0}"#).unwrap())
+///     .build()
+///     .unwrap();
+/// ```
+pub fn set_global_config(json: &str) -> crate::XGBoostResult<()> {
+    use crate::XGBoostError;
+    use std::ffi::CString;
+
+    let nthread = crate::json::lookup(json, &["nthread"])?;
+    if let Some(nthread) = &nthread {
+        if nthread.trim().parse::<u32>().is_err() {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Global nthread must be a non-negative integer, got {}",
+                nthread
+            )));
+        }
+    }
+
+    let json_c_str = CString::new(json)?;
+
+    XGBoostError::check_return_value(unsafe { sys::XGBSetGlobalConfig(json_c_str.as_ptr()) })
+        .map_err(|e| match e {
+            XGBoostError::Native(msg)
+                if nthread.is_some()
+                    && msg.contains("Unknown global parameters")
+                    && msg.contains("nthread") =>
+            {
+                XGBoostError::Native(format!(
+                    "This XGBoost version has no global nthread setting; set OMP_NUM_THREADS \
+                     before the process starts or nthread on each booster instead: {}",
+                    msg
+                ))
+            }
+            e => e,
+        })
+}
+
+/// Cap the number of OpenMP threads XGBoost uses, before any booster exists
+///
+/// Sets the `nthread` key of the global configuration (see [`set_global_config`]), which
+/// boosters fall back to when they have no `nthread` parameter of their own. This is
+/// meant for multi-process servers, where each worker process should use a share of
+/// the cores rather than all of them.
+///
+/// XGBoost keeps its global configuration per thread, so call this on the thread that
+/// later creates and uses the boosters, e.g. in each forked worker right after the
+/// fork. A booster's own `nthread` parameter still takes precedence.
+///
+/// The `OMP_NUM_THREADS` environment variable is the other way to cap the threads. The
+/// OpenMP runtime reads it once when it starts, so it must be set before the process
+/// is launched, and it then sets the default for everything in the process that uses
+/// OpenMP, not just XGBoost. `set_num_threads` can be called at any point and overrides
+/// it for XGBoost.
+///
+/// # Arguments
+/// * `n` - Maximum number of threads, or 0 to go back to XGBoost's default of using
+///   every core (or `OMP_NUM_THREADS`, when set)
+///
+/// # Errors
+/// Returns an error if the linked XGBoost has no global `nthread` setting. Older versions
+/// only support the `nthread` parameter on each booster, set with
+/// [`Booster::set_param`](crate::Booster::set_param) or per prediction with
+/// [`PredictConfig::nthread`](crate::PredictConfig::nthread).
+///
+/// # Example
+/// ```no_run
+/// // In each worker process, before loading the model
+/// xgboost_rust::set_num_threads(2).unwrap();
+/// let booster = xgboost_rust::Booster::load("model.json").unwrap();
+/// ```
+pub fn set_num_threads(n: usize) -> crate::XGBoostResult<()> {
+    set_global_config(&format!(r#"{{"nthread": {}}}"#, n))
+}
+
+/// Get XGBoost's global configuration for the calling thread
+///
+/// # Returns
+/// A JSON object of every global setting (see [`set_global_config`])
+///
+/// # Example
+/// ```no_run
+/// let config = xgboost_rust::get_global_config().unwrap();
+/// println!("{}", config);
+/// ```
+pub fn get_global_config() -> crate::XGBoostResult<String> {
+    use crate::XGBoostError;
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    let mut out: *const c_char = ptr::null();
+
+    XGBoostError::check_return_value(unsafe { sys::XGBGetGlobalConfig(&mut out) })?;
+
+    unsafe { crate::model::cstr_to_string(out, "global config") }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+/// Evaluation metrics recorded during training, one entry per boosting round
+///
+/// Returned by [`Booster::train`](crate::Booster::train). Metric keys are
+/// `{name}-{metric}` as in [`parse_eval_result`](crate::parse_eval_result), e.g. `valid-rmse`.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use xgboost_rust::TrainingHistory;
+///
+/// let mut history = TrainingHistory::new();
+/// history.record(0, HashMap::from([("valid-rmse".to_owned(), 0.5)]));
+/// history.record(1, HashMap::from([("valid-rmse".to_owned(), 0.4)]));
+/// assert_eq!(history.metric("valid-rmse"), vec![(0, 0.5), (1, 0.4)]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainingHistory {
+    rounds: Vec<(i32, HashMap<String, f32>)>,
+}
+
+impl TrainingHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the metrics of one boosting round
+    ///
+    /// # Arguments
+    /// * `iteration` - Iteration number of the round
+    /// * `metrics` - Metric values of the round, keyed by `{name}-{metric}`
+    pub fn record(&mut self, iteration: i32, metrics: HashMap<String, f32>) {
+        self.rounds.push((iteration, metrics));
+    }
+
+    /// Get the recorded rounds, in the order they were recorded
+    pub fn rounds(&self) -> &[(i32, HashMap<String, f32>)] {
+        &self.rounds
+    }
+
+    /// Get the values of one metric across rounds, e.g. to plot a learning curve
+    ///
+    /// # Returns
+    /// `(iteration, value)` pairs for every round that recorded `key`
+    pub fn metric(&self, key: &str) -> Vec<(i32, f32)> {
+        self.rounds
+            .iter()
+            .filter_map(|(iteration, metrics)| Some((*iteration, *metrics.get(key)?)))
+            .collect()
+    }
+
+    /// Get the number of recorded rounds
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Check whether no rounds have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+}
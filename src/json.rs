@@ -0,0 +1,426 @@
+use crate::error::{XGBoostError, XGBoostResult};
+
+/// Look up the value at `path` in a JSON document, following object keys
+///
+/// Strings are returned unescaped, other scalars (numbers, booleans, `null`) as their raw
+/// text, and objects or arrays as their raw JSON text. Returns `None` when a key on the
+/// path does not exist.
+pub(crate) fn lookup(json: &str, path: &[&str]) -> XGBoostResult<Option<String>> {
+    let mut parser = Parser {
+        json,
+        bytes: json.as_bytes(),
+        pos: 0,
+    };
+
+    for key in path {
+        if !parser.find_key(key)? {
+            return Ok(None);
+        }
+    }
+
+    parser.skip_whitespace();
+    if parser.peek() == Some(b'"') {
+        return parser.parse_string().map(Some);
+    }
+    let start = parser.pos;
+    parser.skip_value()?;
+    Ok(Some(json[start..parser.pos].to_owned()))
+}
+
+/// Find the first string value of `key` in any object of a JSON document, depth first
+///
+/// Used for the configuration JSON, where a parameter is stored in whichever section
+/// consumes it. Returns `None` when no object has a string member named `key`.
+pub(crate) fn find(json: &str, key: &str) -> XGBoostResult<Option<String>> {
+    let mut parser = Parser {
+        json,
+        bytes: json.as_bytes(),
+        pos: 0,
+    };
+    parser.search(key)
+}
+
+/// Minimal JSON scanner, just enough to navigate the configuration JSON XGBoost produces
+struct Parser<'a> {
+    json: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Move to the value of `key` in the object at the current position
+    fn find_key(&mut self, key: &str) -> XGBoostResult<bool> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'{') {
+            return Ok(false);
+        }
+        self.pos += 1;
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => return Ok(false),
+                Some(b'"') => {}
+                _ => return Err(self.error("expected object key")),
+            }
+            let name = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            if name == key {
+                return Ok(true);
+            }
+            self.skip_value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => return Ok(false),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    /// Search the value at the current position for a string member named `key`
+    fn search(&mut self, key: &str) -> XGBoostResult<Option<String>> {
+        self.skip_whitespace();
+        let close = match self.peek() {
+            Some(b'{') => b'}',
+            Some(b'[') => b']',
+            _ => {
+                self.skip_value()?;
+                return Ok(None);
+            }
+        };
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(None);
+        }
+
+        loop {
+            if close == b'}' {
+                self.skip_whitespace();
+                let name = self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(b':')?;
+                self.skip_whitespace();
+                if name == key && self.peek() == Some(b'"') {
+                    return self.parse_string().map(Some);
+                }
+            }
+            if let Some(value) = self.search(key)? {
+                return Ok(Some(value));
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(None);
+                }
+                _ => return Err(self.error("unterminated object or array")),
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> XGBoostResult<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(open @ (b'{' | b'[')) => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek() == Some(close) {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                loop {
+                    if open == b'{' {
+                        self.skip_whitespace();
+                        self.parse_string()?;
+                        self.skip_whitespace();
+                        self.expect(b':')?;
+                    }
+                    self.skip_value()?;
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(c) if c == close => {
+                            self.pos += 1;
+                            return Ok(());
+                        }
+                        _ => return Err(self.error("unterminated object or array")),
+                    }
+                }
+            }
+            Some(b'"') => self.parse_string().map(|_| ()),
+            Some(_) => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if matches!(c, b',' | b'}' | b']') || c.is_ascii_whitespace() {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("expected value"));
+                }
+                Ok(())
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_string(&mut self) -> XGBoostResult<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(c) = self.peek() {
+                if c == b'"' || c == b'\\' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            out.push_str(&self.json[start..self.pos]);
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escaped {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => out.push(self.parse_unicode_escape()?),
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                _ => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// Parse the `XXXX` of a `\uXXXX` escape, including a following low surrogate
+    fn parse_unicode_escape(&mut self) -> XGBoostResult<char> {
+        let high = self.parse_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.json[self.pos..].starts_with("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.pos += 2;
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex4(&mut self) -> XGBoostResult<u32> {
+        let digits = self
+            .json
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        let value =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    fn expect(&mut self, c: u8) -> XGBoostResult<()> {
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("expected '{}'", c as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> XGBoostError {
+        XGBoostError::Native(format!("Invalid JSON at byte {}: {}", self.pos, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, lookup};
+
+    #[test]
+    fn lookup_unescapes_strings() {
+        let json = r#"{"a": "quote \" backslash \\ slash \/ controls \b\f\n\r\t end"}"#;
+        assert_eq!(
+            lookup(json, &["a"]).unwrap().as_deref(),
+            Some("quote \" backslash \\ slash / controls \u{8}\u{c}\n\r\t end")
+        );
+    }
+
+    #[test]
+    fn lookup_decodes_unicode_escapes() {
+        let json = r#"{"bmp": "caf\u00e9", "pair": "\ud83d\ude00!", "upper": "\uD834\uDD1E"}"#;
+        assert_eq!(lookup(json, &["bmp"]).unwrap().as_deref(), Some("café"));
+        assert_eq!(lookup(json, &["pair"]).unwrap().as_deref(), Some("😀!"));
+        assert_eq!(lookup(json, &["upper"]).unwrap().as_deref(), Some("𝄞"));
+    }
+
+    #[test]
+    fn lookup_rejects_unpaired_surrogates() {
+        for json in [
+            r#"{"a": "\ud83d"}"#,
+            r#"{"a": "\ud83dx"}"#,
+            r#"{"a": "\ud83dA"}"#,
+            r#"{"a": "\ude00"}"#,
+        ] {
+            assert!(lookup(json, &["a"]).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn lookup_follows_nested_objects() {
+        let json = r#"{"learner": {"objective": {"name": "reg:squarederror"},
+            "attributes": {}, "trees": [1, [2, 3], {"x": "y"}], "num": 1.5e-3, "flag": true,
+            "none": null}}"#;
+        assert_eq!(
+            lookup(json, &["learner", "objective", "name"])
+                .unwrap()
+                .as_deref(),
+            Some("reg:squarederror")
+        );
+        assert_eq!(
+            lookup(json, &["learner", "trees"]).unwrap().as_deref(),
+            Some(r#"[1, [2, 3], {"x": "y"}]"#)
+        );
+        assert_eq!(
+            lookup(json, &["learner", "attributes"]).unwrap().as_deref(),
+            Some("{}")
+        );
+        assert_eq!(
+            lookup(json, &["learner", "num"]).unwrap().as_deref(),
+            Some("1.5e-3")
+        );
+        assert_eq!(
+            lookup(json, &["learner", "flag"]).unwrap().as_deref(),
+            Some("true")
+        );
+        assert_eq!(
+            lookup(json, &["learner", "none"]).unwrap().as_deref(),
+            Some("null")
+        );
+    }
+
+    #[test]
+    fn lookup_returns_none_for_missing_keys() {
+        let json = r#"{"a": {"b": "c"}, "d": [1]}"#;
+        assert_eq!(lookup(json, &["x"]).unwrap(), None);
+        assert_eq!(lookup(json, &["a", "x"]).unwrap(), None);
+        // Keys are only followed through objects
+        assert_eq!(lookup(json, &["a", "b", "c"]).unwrap(), None);
+        assert_eq!(lookup(json, &["d", "0"]).unwrap(), None);
+        // Keys are matched after unescaping
+        assert_eq!(
+            lookup(r#"{"k\u0065y": "v"}"#, &["key"]).unwrap().as_deref(),
+            Some("v")
+        );
+    }
+
+    #[test]
+    fn find_returns_first_match_depth_first() {
+        let json = r#"{"outer": {"inner": {"key": "deepest"}}, "key": "top",
+            "later": {"key": "later"}}"#;
+        assert_eq!(find(json, "key").unwrap().as_deref(), Some("deepest"));
+
+        let json = r#"{"list": [{"other": 1}, [{"key": "in array"}]], "key": "top"}"#;
+        assert_eq!(find(json, "key").unwrap().as_deref(), Some("in array"));
+
+        let json = r#"{"a": {"b": "1"}, "c": {"d": {"b": "2"}}}"#;
+        assert_eq!(find(json, "b").unwrap().as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn find_skips_values_that_are_not_strings() {
+        let json = r#"{"key": 1, "nested": {"key": {"key": "inside"}}, "last": {"key": "x"}}"#;
+        assert_eq!(find(json, "key").unwrap().as_deref(), Some("inside"));
+        assert_eq!(find(r#"{"key": [1, 2]}"#, "key").unwrap(), None);
+        assert_eq!(find(r#"{"a": {}, "b": []}"#, "key").unwrap(), None);
+        assert_eq!(find(r#""key""#, "key").unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_input_is_an_error_not_a_panic() {
+        let inputs = [
+            "",
+            "{",
+            "[",
+            r#"{"a""#,
+            r#"{"a":"#,
+            r#"{"a": "unterminated"#,
+            r#"{"a": "bad escape \q"}"#,
+            r#"{"a": "short \u12"}"#,
+            r#"{"a": "not hex \uzzzz"}"#,
+            r#"{"a": "ends in escape \"#,
+            r#"{"a": 1 "b": 2}"#,
+            r#"{"a": [1, 2}"#,
+            r#"{"a": {"b": 1]"#,
+            r#"{1: 2}"#,
+            r#"{"a" 1}"#,
+            r#"{"a": }"#,
+            r#"{"é": "ü", "#,
+        ];
+        for json in inputs {
+            // Only the parts actually scanned are checked, so some inputs may still succeed
+            let _ = lookup(json, &["a"]);
+            let _ = lookup(json, &["z"]);
+            let _ = find(json, "z");
+        }
+
+        assert!(lookup("{", &["a"]).is_err());
+        assert!(lookup(r#"{"a": "unterminated"#, &["a"]).is_err());
+        assert!(lookup(r#"{"a": "bad escape \q"}"#, &["a"]).is_err());
+        assert!(lookup(r#"{"a": "short \u12"}"#, &["a"]).is_err());
+        assert!(lookup(r#"{"a": 1 "b": 2}"#, &["b"]).is_err());
+        assert!(lookup(r#"{"a": }"#, &["b"]).is_err());
+        assert!(lookup(r#"{1: 2}"#, &["a"]).is_err());
+        assert!(find(r#"{"a": [1, 2}"#, "z").is_err());
+        assert!(find(r#"{"a": {"b": 1]"#, "z").is_err());
+        assert!(find(r#"{"é": "ü", "#, "z").is_err());
+    }
+
+    #[test]
+    fn truncated_documents_never_panic() {
+        let json = r#"{"a": {"b": ["c", 1.5, {"d": "é😀\n"}]}, "key": "value"}"#;
+        for end in 0..=json.len() {
+            let Some(prefix) = json.get(..end) else {
+                continue;
+            };
+            let _ = lookup(prefix, &["a", "b"]);
+            let _ = lookup(prefix, &["key"]);
+            let _ = find(prefix, "d");
+            let _ = find(prefix, "key");
+        }
+        assert_eq!(find(json, "d").unwrap().as_deref(), Some("é😀\n"));
+    }
+}
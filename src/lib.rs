@@ -1,22 +1,117 @@
 // Include the XGBoost C API bindings
 mod sys;
 
+// Minimal JSON lookup for the configuration XGBoost returns
+mod json;
+
 mod error;
 pub use crate::error::{XGBoostError, XGBoostResult};
 
+mod dmatrix;
+pub use crate::dmatrix::{DMatrix, Shape};
+
+#[cfg(xgboost_external_memory)]
+mod data_iter;
+#[cfg(xgboost_external_memory)]
+pub use crate::data_iter::{Batch, DataIter};
+
+mod builder;
+pub use crate::builder::BoosterBuilder;
+
+mod history;
+pub use crate::history::TrainingHistory;
+
+mod predictions;
+pub use crate::predictions::Predictions;
+
 mod model;
-pub use crate::model::Booster;
+#[cfg(xgboost_save_to_buffer)]
+pub use crate::model::ModelFormat;
+pub use crate::model::{
+    parse_eval_result, read_predictions, Booster, DumpFormat, ModelMetadata, PredictConfig,
+};
+
+mod global;
+pub use crate::global::{
+    check_xgboost_version, get_global_config, set_global_config, set_num_threads,
+    xgboost_compiled_version, xgboost_last_error, xgboost_version,
+};
+#[cfg(xgboost_build_info)]
+pub use crate::global::{xgboost_build_info, xgboost_has_cuda};
+
+#[cfg(feature = "polars")]
+mod polars_ext;
+#[cfg(feature = "polars")]
+pub use crate::polars_ext::{
+    dataframe_to_dense, dataframe_to_dense_with_nan_handling, dataframe_to_dmatrix,
+    dataframe_to_dmatrix_with_nan_handling, score_file, BoosterPolarsExt, NanHandling,
+    PreparedFrame,
+};
+
+#[cfg(feature = "linfa")]
+mod linfa_ext;
 
 // Re-export prediction option constants for convenience
 pub mod predict_option {
-    /// Normal prediction, output is the transformed probability
+    use crate::error::{XGBoostError, XGBoostResult};
+
+    /// Output the untransformed margin value instead of the transformed prediction
     pub const OUTPUT_MARGIN: u32 = 0x01;
-    /// Output the untransformed margin value
-    pub const PRED_LEAF: u32 = 0x02;
     /// Output the leaf index of trees
-    pub const PRED_CONTRIBS: u32 = 0x04;
+    pub const PRED_LEAF: u32 = 0x02;
     /// Output feature contributions (SHAP values)
+    pub const PRED_CONTRIBS: u32 = 0x04;
+    /// Approximate the contributions or interactions instead of computing exact SHAP values
     pub const PRED_APPROX_CONTRIBS: u32 = 0x08;
     /// Output feature interaction contributions
     pub const PRED_INTERACTIONS: u32 = 0x10;
+
+    /// Check that an option mask is a valid combination of options
+    ///
+    /// XGBoost produces one kind of output per call, so at most one of `PRED_LEAF`,
+    /// `PRED_CONTRIBS` and `PRED_INTERACTIONS` may be set, and `PRED_APPROX_CONTRIBS`
+    /// requires `PRED_CONTRIBS` or `PRED_INTERACTIONS`. Every prediction method checks
+    /// the mask before calling XGBoost.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::predict_option::{self, PRED_APPROX_CONTRIBS, PRED_CONTRIBS, PRED_LEAF};
+    ///
+    /// assert!(predict_option::validate(PRED_CONTRIBS | PRED_APPROX_CONTRIBS).is_ok());
+    /// assert!(predict_option::validate(PRED_LEAF | PRED_CONTRIBS).is_err());
+    /// ```
+    pub fn validate(option_mask: u32) -> XGBoostResult<()> {
+        let known =
+            OUTPUT_MARGIN | PRED_LEAF | PRED_CONTRIBS | PRED_APPROX_CONTRIBS | PRED_INTERACTIONS;
+        if option_mask & !known != 0 {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Unknown prediction option bits {:#x}",
+                option_mask & !known
+            )));
+        }
+
+        let outputs: Vec<&str> = [
+            (PRED_LEAF, "PRED_LEAF"),
+            (PRED_CONTRIBS, "PRED_CONTRIBS"),
+            (PRED_INTERACTIONS, "PRED_INTERACTIONS"),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| option_mask & bit != 0)
+        .map(|(_, name)| name)
+        .collect();
+        if outputs.len() > 1 {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Prediction options {} cannot be combined, request one at a time",
+                outputs.join(", ")
+            )));
+        }
+        if option_mask & PRED_APPROX_CONTRIBS != 0
+            && option_mask & (PRED_CONTRIBS | PRED_INTERACTIONS) == 0
+        {
+            return Err(XGBoostError::InvalidInput(
+                "PRED_APPROX_CONTRIBS requires PRED_CONTRIBS or PRED_INTERACTIONS".to_owned(),
+            ));
+        }
+        Ok(())
+    }
 }
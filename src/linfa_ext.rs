@@ -0,0 +1,53 @@
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::Booster;
+use linfa::traits::Predict;
+use linfa::DatasetBase;
+use ndarray::Array2;
+
+/// linfa integration for [`Booster`]
+///
+/// Predicts on the records of a linfa dataset, one row per sample, so a trained booster can
+/// stand in for a linfa model, e.g. to score a validation split. The targets of the dataset
+/// are not used. The predictions have one row per sample and one column per output: a single
+/// column for regression and binary classification, one per class for `multi:softprob`.
+///
+/// Unlike linfa's own models, prediction returns a [`XGBoostResult`], since XGBoost rejects
+/// records whose number of features does not match the model.
+///
+/// [`Booster::predict`] takes precedence over the trait method in method-call syntax, so call
+/// it as `Predict::predict(&booster, &dataset)`.
+///
+/// Enabled with the `linfa` feature.
+///
+/// # Example
+/// ```
+/// use linfa::traits::Predict;
+/// use linfa::Dataset;
+/// use ndarray::array;
+/// use xgboost_rust::{BoosterBuilder, DMatrix, XGBoostResult};
+///
+/// let records = array![[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+/// let mut dtrain = DMatrix::from_array2(&records, f32::NAN).unwrap();
+/// dtrain.set_labels(&[0.0, 1.0, 1.0]).unwrap();
+/// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+///
+/// let dataset = Dataset::new(records, array![[0.0], [1.0], [1.0]]);
+/// let predictions: XGBoostResult<_> = Predict::predict(&booster, &dataset);
+/// assert_eq!(predictions.unwrap().dim(), (3, 1));
+/// ```
+impl<'a, T> Predict<&'a DatasetBase<Array2<f32>, T>, XGBoostResult<Array2<f32>>> for Booster {
+    fn predict(&self, dataset: &'a DatasetBase<Array2<f32>, T>) -> XGBoostResult<Array2<f32>> {
+        let records = dataset.records.as_standard_layout();
+        let (num_rows, num_features) = records.dim();
+        let data = records.as_slice().ok_or_else(|| {
+            XGBoostError::InvalidInput(
+                "Records could not be copied into row-major layout".to_owned(),
+            )
+        })?;
+
+        let (predictions, shape) =
+            self.predict_with_shape(data, num_rows, num_features, 0, false)?;
+        Array2::from_shape_vec((shape.rows, shape.cols), predictions)
+            .map_err(|e| XGBoostError::InvalidInput(format!("Unexpected prediction shape: {}", e)))
+    }
+}
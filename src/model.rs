@@ -1,8 +1,115 @@
+use crate::dmatrix::{validate_dense_shape, DMatrix, Shape};
 use crate::error::{XGBoostError, XGBoostResult};
+use crate::history::TrainingHistory;
+use crate::json;
+use crate::predictions::Predictions;
 use crate::sys;
-use std::ffi::CString;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::os::raw::c_char;
 use std::path::Path;
 use std::ptr;
+use std::sync::{Once, OnceLock};
+
+/// Output format for model dumps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Plain text, one line per node
+    Text,
+    /// JSON, one object per tree
+    Json,
+}
+
+impl DumpFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DumpFormat::Text => "text",
+            DumpFormat::Json => "json",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Text => "txt",
+            DumpFormat::Json => "json",
+        }
+    }
+}
+
+/// Serialization format for models saved to memory
+///
+/// Requires XGBoost ≥ 1.6.
+#[cfg(xgboost_save_to_buffer)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFormat {
+    /// JSON, the same as saving to a `.json` file
+    Json,
+    /// Universal Binary JSON, a binary encoding of the JSON model
+    Ubj,
+    /// XGBoost's old custom binary format, only for consumers that cannot read the others.
+    /// Newer XGBoost versions may refuse to write it
+    Deprecated,
+}
+
+#[cfg(xgboost_save_to_buffer)]
+impl ModelFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModelFormat::Json => "json",
+            ModelFormat::Ubj => "ubj",
+            ModelFormat::Deprecated => "deprecated",
+        }
+    }
+}
+
+/// Options for [`Booster::predict_with_config`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PredictConfig {
+    /// Prediction options (see `predict_option` module)
+    pub option_mask: u32,
+    /// Whether this is for training (false for inference, see [`Booster::predict`])
+    pub training: bool,
+    /// Number of threads to use for this prediction, or `None` to use the booster's setting
+    pub nthread: Option<usize>,
+    /// Use only the first `iteration_end` boosting rounds, e.g. `best_iteration + 1` for a
+    /// model trained with early stopping, or `None` to use every round. Must not exceed
+    /// [`Booster::num_boosted_rounds`]
+    pub iteration_end: Option<usize>,
+    /// Give the output the same number of dimensions whatever the model and options, e.g.
+    /// `[rows, 1]` rather than `[rows]` for a single output. Only affects the shape
+    /// returned by [`Booster::predict_dmatrix_with_shape`], not the values
+    pub strict_shape: bool,
+}
+
+impl PredictConfig {
+    /// Check that `option_mask` is a valid combination of options
+    ///
+    /// See [`predict_option::validate`](crate::predict_option::validate).
+    pub fn validate(&self) -> XGBoostResult<()> {
+        crate::predict_option::validate(self.option_mask)
+    }
+}
+
+/// Summary of a model, read from its configuration by [`Booster::model_metadata`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelMetadata {
+    /// Number of features the model expects
+    pub num_features: usize,
+    /// Number of classes of a multiclass model, or 1 for regression and binary models
+    pub num_class: usize,
+    /// Name of the objective, e.g. `binary:logistic` or `multi:softprob`
+    pub objective: String,
+    /// Name of the booster: `gbtree`, `gblinear` or `dart`
+    pub booster_type: String,
+    /// The `base_score` the model starts every prediction from, on the scale of the
+    /// objective's output. Usually one value; newer XGBoost versions store one per output
+    /// for multi-output models
+    pub base_score: Vec<f32>,
+}
 
 /// An XGBoost Booster for making predictions.
 ///
@@ -48,6 +155,9 @@ use std::ptr;
 ///    ```
 pub struct Booster {
     handle: sys::BoosterHandle,
+    // Whether the booster is dart, read on the first prediction with `training` set and
+    // cleared whenever the parameters or configuration change
+    is_dart: OnceLock<bool>,
 }
 
 // Thread safety implementation based on XGBoost version
@@ -63,6 +173,57 @@ unsafe impl Sync for Booster {}
 // Users should wrap in Arc<Mutex<Booster>> or use one Booster per thread.
 
 impl Booster {
+    /// Create an untrained booster, using `cache` as its prediction cache
+    ///
+    /// XGBoost keeps the predictions of the matrices in `cache` between calls and only
+    /// adds the trees trained since the last call, so evaluating the training and
+    /// validation data after every round does not re-run the whole model. Predictions on
+    /// cached matrices are the same as on any other matrix. Usually created through
+    /// [`BoosterBuilder`](crate::BoosterBuilder), which also sets parameters.
+    ///
+    /// # Arguments
+    /// * `cache` - Matrices to keep in the prediction cache, usually the training data
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    /// let mut dtrain = DMatrix::from_dense(&data, 4, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+    ///
+    /// let mut booster = Booster::new(&[&dtrain]).unwrap();
+    /// for iteration in 0..3 {
+    ///     booster.update_one_iter(iteration, &dtrain).unwrap();
+    /// }
+    ///
+    /// let uncached = DMatrix::from_dense(&data, 4, 2, f32::NAN).unwrap();
+    /// assert_eq!(
+    ///     booster.predict_dmatrix(&dtrain, 0, false).unwrap(),
+    ///     booster.predict_dmatrix(&uncached, 0, false).unwrap()
+    /// );
+    /// ```
+    pub fn new(cache: &[&DMatrix]) -> XGBoostResult<Self> {
+        crate::global::check_xgboost_version_once()?;
+
+        let handles: Vec<sys::DMatrixHandle> =
+            cache.iter().map(|dmatrix| dmatrix.handle()).collect();
+
+        let mut handle: sys::BoosterHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterCreate(handles.as_ptr(), handles.len() as u64, &mut handle)
+        })?;
+
+        Ok(Booster::from_handle(handle))
+    }
+
+    fn from_handle(handle: sys::BoosterHandle) -> Self {
+        Booster {
+            handle,
+            is_dart: OnceLock::new(),
+        }
+    }
+
     /// Load a model from a file
     ///
     /// # Arguments
@@ -75,12 +236,12 @@ impl Booster {
     /// let booster = Booster::load("model.json").unwrap();
     /// ```
     pub fn load<P: AsRef<Path>>(path: P) -> XGBoostResult<Self> {
-        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
-            description: "Path contains invalid UTF-8 characters".to_string(),
-        })?;
-        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
-            description: format!("Path contains NUL byte: {}", e),
-        })?;
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(XGBoostError::FileNotFound(path.to_path_buf()));
+        }
+        let path_c_str = path_to_c_string(path)?;
+        crate::global::check_xgboost_version_once()?;
 
         // Create a booster first
         let mut handle: sys::BoosterHandle = ptr::null_mut();
@@ -100,7 +261,7 @@ impl Booster {
             return Err(e);
         }
 
-        Ok(Booster { handle })
+        Ok(Booster::from_handle(handle))
     }
 
     /// Load a model from a memory buffer
@@ -117,6 +278,8 @@ impl Booster {
     /// let booster = Booster::load_from_buffer(&buffer).unwrap();
     /// ```
     pub fn load_from_buffer(buffer: &[u8]) -> XGBoostResult<Self> {
+        crate::global::check_xgboost_version_once()?;
+
         // Create a booster first
         let mut handle: sys::BoosterHandle = ptr::null_mut();
         XGBoostError::check_return_value(unsafe {
@@ -139,7 +302,202 @@ impl Booster {
             return Err(e);
         }
 
-        Ok(Booster { handle })
+        Ok(Booster::from_handle(handle))
+    }
+
+    /// Save the model to a memory buffer
+    ///
+    /// The buffer can be loaded back with [`Booster::load_from_buffer`].
+    /// Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `format` - Serialization format of the model
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, ModelFormat};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let buffer = booster.save_to_buffer(ModelFormat::Ubj).unwrap();
+    /// let copy = Booster::load_from_buffer(&buffer).unwrap();
+    /// ```
+    #[cfg(xgboost_save_to_buffer)]
+    pub fn save_to_buffer(&self, format: ModelFormat) -> XGBoostResult<Vec<u8>> {
+        let config = CString::new(format!("{{\"format\": \"{}\"}}", format.as_str())).unwrap();
+
+        let mut out_len: u64 = 0;
+        let mut out_buffer: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveModelToBuffer(
+                self.handle,
+                config.as_ptr(),
+                &mut out_len,
+                &mut out_buffer,
+            )
+        })?;
+
+        if out_len == 0 {
+            return Ok(Vec::new());
+        }
+        if out_buffer.is_null() {
+            return Err(XGBoostError::Native(
+                "XGBoost returned null model buffer".to_owned(),
+            ));
+        }
+
+        let bytes =
+            unsafe { std::slice::from_raw_parts(out_buffer as *const u8, out_len as usize) };
+        Ok(bytes.to_vec())
+    }
+
+    /// Create an independent copy of the booster
+    ///
+    /// The model is saved to a UBJ buffer and loaded into a new booster, so this costs
+    /// time and memory proportional to the model size. The copy has its own handle and
+    /// prediction cache. Parameters set after loading that are not part of the model
+    /// (such as `nthread`) are not copied. Requires XGBoost ≥ 1.6.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::thread;
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let worker_booster = booster.try_clone().unwrap();
+    /// thread::spawn(move || {
+    ///     let predictions = worker_booster.predict(&[1.0, 2.0], 1, 2, 0, false).unwrap();
+    /// });
+    /// ```
+    #[cfg(xgboost_save_to_buffer)]
+    pub fn try_clone(&self) -> XGBoostResult<Self> {
+        Booster::load_from_buffer(&self.save_to_buffer(ModelFormat::Ubj)?)
+    }
+
+    /// Download a model over HTTP(S) and load it
+    ///
+    /// The model is read into memory and loaded as with [`Booster::load_from_buffer`],
+    /// so it can be in any format XGBoost saves. Objects in S3 or other object stores are
+    /// loaded through a public or presigned HTTPS URL. Proxies are taken from the standard
+    /// `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables. Requires the
+    /// `remote` feature.
+    ///
+    /// # Arguments
+    /// * `url` - URL of the model file
+    ///
+    /// # Returns
+    /// The loaded booster, or [`XGBoostError::Http`] if the download fails or the server
+    /// does not answer with a success status
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load_from_url("https://example.com/models/model.json").unwrap();
+    /// ```
+    #[cfg(feature = "remote")]
+    pub fn load_from_url(url: &str) -> XGBoostResult<Self> {
+        let timeout = std::time::Duration::from_secs(30);
+        let agent = ureq::AgentBuilder::new()
+            .try_proxy_from_env(true)
+            .timeout_connect(timeout)
+            .timeout_read(timeout)
+            .build();
+
+        let response = agent.get(url).call().map_err(|e| match e {
+            ureq::Error::Status(status, _) => {
+                XGBoostError::Http(format!("Failed to download {}: HTTP {}", url, status))
+            }
+            // The message of a transport error starts with the URL, when it has one
+            ureq::Error::Transport(transport) if transport.url().is_some() => {
+                XGBoostError::Http(format!("Failed to download {}", transport))
+            }
+            e => XGBoostError::Http(format!("Failed to download {}: {}", url, e)),
+        })?;
+
+        let mut buffer = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut buffer)
+            .map_err(|e| XGBoostError::Http(format!("Failed to download {}: {}", url, e)))?;
+        Booster::load_from_buffer(&buffer)
+    }
+
+    /// Snapshot the full state of the booster, for checkpointing
+    ///
+    /// [`Booster::save_to_buffer`] saves the model: its trees, objective and attributes.
+    /// A snapshot also holds the configuration, including training parameters such as
+    /// `eta` or `max_depth` and the evaluation metrics, so a booster restored with
+    /// [`Booster::unserialize`] continues training exactly as the original would. The
+    /// encoding is internal to XGBoost and may not load in other versions, so use the
+    /// model formats for storing or sharing models.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{Booster, BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .param("max_depth", "3")
+    ///     .num_round(2)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let snapshot = booster.serialize().unwrap();
+    /// let restored = Booster::unserialize(&snapshot).unwrap();
+    /// assert!(restored.save_config().unwrap().contains(r#""max_depth":"3""#));
+    /// ```
+    pub fn serialize(&self) -> XGBoostResult<Vec<u8>> {
+        let mut out_len: u64 = 0;
+        let mut out_buffer: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSerializeToBuffer(self.handle, &mut out_len, &mut out_buffer)
+        })?;
+
+        if out_len == 0 {
+            return Ok(Vec::new());
+        }
+        if out_buffer.is_null() {
+            return Err(XGBoostError::Native(
+                "XGBoost returned null snapshot buffer".to_owned(),
+            ));
+        }
+
+        let bytes =
+            unsafe { std::slice::from_raw_parts(out_buffer as *const u8, out_len as usize) };
+        Ok(bytes.to_vec())
+    }
+
+    /// Restore a booster from a snapshot made by [`Booster::serialize`]
+    ///
+    /// # Arguments
+    /// * `buffer` - Snapshot bytes
+    pub fn unserialize(buffer: &[u8]) -> XGBoostResult<Self> {
+        crate::global::check_xgboost_version_once()?;
+
+        let mut handle: sys::BoosterHandle = ptr::null_mut();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterCreate(ptr::null(), 0, &mut handle)
+        })?;
+
+        let result = XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterUnserializeFromBuffer(
+                handle,
+                buffer.as_ptr() as *const std::os::raw::c_void,
+                buffer.len() as u64,
+            )
+        });
+
+        if let Err(e) = result {
+            unsafe {
+                sys::XGBoosterFree(handle);
+            }
+            return Err(e);
+        }
+
+        Ok(Booster::from_handle(handle))
     }
 
     /// Make predictions on data
@@ -149,7 +507,7 @@ impl Booster {
     /// * `num_rows` - Number of rows in the data
     /// * `num_features` - Number of features per row
     /// * `option_mask` - Prediction options (see `predict_option` module)
-    /// * `training` - Whether this is for training (false for inference)
+    /// * `training` - Whether this is for training (false for inference, see below)
     ///
     /// # Returns
     /// A vector of prediction values
@@ -162,6 +520,44 @@ impl Booster {
     /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
     /// let predictions = booster.predict(&data, 2, 2, 0, false).unwrap();
     /// ```
+    ///
+    /// See [`Booster::predict_typed`] for predictions that keep their shape.
+    ///
+    /// # The `training` flag
+    /// Pass `false` to score data. With `true`, XGBoost predicts as it does within a
+    /// training round, which is only needed to compute gradients, e.g. for a custom
+    /// objective. For dart models this applies dropout: a random subset of trees is left
+    /// out of every prediction, so the same rows give different predictions from call to
+    /// call. In debug builds, the first time a dart model predicts with `training` set, a
+    /// warning is printed to stderr; release builds print nothing, and skip the check. Every
+    /// prediction method taking a `training` argument behaves this way.
+    ///
+    /// # Missing values
+    /// `f32::NAN` entries in `data` are treated as missing and follow the default
+    /// direction learned at each split, so they never turn the prediction into NaN.
+    /// Encode missing features as `f32::NAN` rather than a sentinel such as `0.0` or `-999.0`;
+    /// use [`Booster::predict_dmatrix`] with a [`DMatrix`] built with a custom `missing`
+    /// value if the data already uses a sentinel.
+    ///
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let nan = f32::NAN;
+    /// let data = [1.0, nan, 2.0, 1.0, nan, 3.0, 4.0, nan, 5.0, 2.0, nan, 6.0];
+    /// let mut dtrain = DMatrix::from_dense(&data, 6, 2, nan).unwrap();
+    /// dtrain.set_labels(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .param("min_child_weight", "0")
+    ///     .num_round(5)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let predictions = booster
+    ///     .predict(&[nan, 2.0, 3.0, nan, nan, nan], 3, 2, 0, false)
+    ///     .unwrap();
+    /// assert_eq!(predictions.len(), 3);
+    /// assert!(predictions.iter().all(|p| p.is_finite()));
+    /// ```
     pub fn predict(
         &self,
         data: &[f32],
@@ -170,120 +566,3035 @@ impl Booster {
         option_mask: u32,
         training: bool,
     ) -> XGBoostResult<Vec<f32>> {
-        // Validate input dimensions
-        let expected_len = num_rows
-            .checked_mul(num_features)
-            .ok_or_else(|| XGBoostError {
-                description: format!(
-                    "Integer overflow: num_rows ({}) * num_features ({}) exceeds usize::MAX",
-                    num_rows, num_features
-                ),
-            })?;
-
-        if data.len() != expected_len {
-            return Err(XGBoostError {
-                description: format!(
-                    "Data length mismatch: expected {} elements ({}×{}), got {}",
-                    expected_len,
-                    num_rows,
-                    num_features,
-                    data.len()
-                ),
-            });
-        }
-
-        // Create DMatrix from data
-        let mut dmatrix_handle: sys::DMatrixHandle = ptr::null_mut();
-
-        XGBoostError::check_return_value(unsafe {
-            sys::XGDMatrixCreateFromMat(
-                data.as_ptr(),
-                num_rows as u64,
-                num_features as u64,
-                f32::NAN,
-                &mut dmatrix_handle,
-            )
-        })?;
-
-        // RAII guard to ensure DMatrix is always freed
-        struct DMatrixGuard(sys::DMatrixHandle);
-        impl Drop for DMatrixGuard {
-            fn drop(&mut self) {
-                unsafe {
-                    sys::XGDMatrixFree(self.0);
-                }
-            }
-        }
-        let _guard = DMatrixGuard(dmatrix_handle);
-
-        // Make prediction
-        let mut out_len: u64 = 0;
-        let mut out_result: *const f32 = ptr::null();
-
-        XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterPredict(
-                self.handle,
-                dmatrix_handle,
-                option_mask as i32,
-                0, // ntree_limit (0 means use all trees)
-                training as i32,
-                &mut out_len,
-                &mut out_result,
-            )
-        })?;
-
-        // Validate output pointers
-        if out_result.is_null() || out_len == 0 {
-            return Err(XGBoostError {
-                description: "XGBoost returned null or empty prediction result".to_string(),
-            });
-        }
-
-        // Copy results to a Vec
-        let results = unsafe { std::slice::from_raw_parts(out_result, out_len as usize).to_vec() };
-
-        // DMatrix will be automatically freed when _guard goes out of scope
-
+        let mut results = Vec::new();
+        self.predict_into(
+            data,
+            num_rows,
+            num_features,
+            option_mask,
+            training,
+            &mut results,
+        )?;
         Ok(results)
     }
 
-    /// Get the number of features the model expects
+    /// Make predictions on dense data, writing them into an existing buffer
     ///
-    /// # Returns
-    /// The number of features
-    pub fn num_features(&self) -> XGBoostResult<usize> {
-        let mut out_num_features: u64 = 0;
-
-        XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterGetNumFeature(self.handle, &mut out_num_features)
-        })?;
+    /// Like [`Booster::predict`], but `out` is cleared and filled with the results, so
+    /// reusing one buffer across calls avoids allocating once it has grown large enough.
+    /// On error, `out` is left unchanged.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `out` - Buffer to receive the prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let mut predictions = Vec::new();
+    /// for batch in [vec![1.0, 2.0, 3.0, 4.0], vec![5.0, 6.0, 7.0, 8.0]] {
+    ///     booster.predict_into(&batch, 2, 2, 0, false, &mut predictions).unwrap();
+    ///     println!("{:?}", predictions);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// The temporary matrix is freed whether or not the prediction succeeds, so a caller
+    /// can keep predicting on a booster that rejected some input:
+    ///
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    ///
+    /// let mut predictions = vec![0.5];
+    /// for _ in 0..1000 {
+    ///     // The model has 2 features, so rows of 3 are rejected by XGBoost
+    ///     let result = booster.predict_into(&[1.0, 2.0, 3.0], 1, 3, 0, false, &mut predictions);
+    ///     assert!(result.is_err());
+    ///     assert_eq!(predictions, vec![0.5]);
+    /// }
+    /// booster.predict_into(&[1.0, 2.0], 1, 2, 0, false, &mut predictions).unwrap();
+    /// assert_eq!(predictions.len(), 1);
+    /// ```
+    pub fn predict_into(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+        out: &mut Vec<f32>,
+    ) -> XGBoostResult<()> {
+        self.warn_dart_training(training);
+        // The matrix is freed when it goes out of scope, including when prediction fails
+        let dmatrix = DMatrix::from_dense(data, num_rows, num_features, f32::NAN)?;
+        self.predict_dmatrix_into(&dmatrix, option_mask, training, 0, out)
+    }
 
-        Ok(out_num_features as usize)
+    /// Predict untransformed margins, e.g. logits for `binary:logistic`
+    ///
+    /// Equivalent to [`Booster::predict`] with exactly the
+    /// [`OUTPUT_MARGIN`](crate::predict_option::OUTPUT_MARGIN) option set.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let logits = booster.predict_margin(&data, 2, 2).unwrap();
+    /// ```
+    pub fn predict_margin(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.predict(
+            data,
+            num_rows,
+            num_features,
+            crate::predict_option::OUTPUT_MARGIN,
+            false,
+        )
     }
 
-    /// Save the model to a file
+    /// Predict transformed values, e.g. probabilities for `binary:logistic`
+    ///
+    /// Equivalent to [`Booster::predict`] with no options set. For regression objectives
+    /// this is the predicted value itself.
     ///
     /// # Arguments
-    /// * `path` - Path where to save the model
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
     ///
     /// # Example
     /// ```no_run
     /// use xgboost_rust::Booster;
     ///
     /// let booster = Booster::load("model.json").unwrap();
-    /// booster.save("model_copy.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let probabilities = booster.predict_proba(&data, 2, 2).unwrap();
     /// ```
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
-        let path_str = path.as_ref().to_str().ok_or_else(|| XGBoostError {
-            description: "Path contains invalid UTF-8 characters".to_string(),
-        })?;
-        let path_c_str = CString::new(path_str).map_err(|e| XGBoostError {
-            description: format!("Path contains NUL byte: {}", e),
-        })?;
+    pub fn predict_proba(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.predict(data, num_rows, num_features, 0, false)
+    }
 
-        XGBoostError::check_return_value(unsafe {
-            sys::XGBoosterSaveModel(self.handle, path_c_str.as_ptr())
-        })
+    /// Predict the class index of each row with a classification model
+    ///
+    /// How the predictions are turned into classes depends on the model's objective (see
+    /// [`Booster::objective`]):
+    /// * `multi:softprob` - the class with the highest probability
+    /// * `multi:softmax` and `binary:hinge` - the predicted class itself
+    /// * `binary:logistic` - 1 if the probability is above 0.5, otherwise 0
+    /// * `binary:logitraw` - 1 if the margin is above 0, otherwise 0
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Returns
+    /// One class index per row, or an error for objectives that do not predict classes,
+    /// such as regression and ranking
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let data = [0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 0.1, 0.0, 1.1, 1.0, 2.1, 2.0];
+    /// let mut dtrain = DMatrix::from_dense(&data, 6, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0, 2.0, 0.0, 1.0, 2.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .objective("multi:softprob")
+    ///     .param("num_class", "3")
+    ///     .param("min_child_weight", "0")
+    ///     .num_round(10)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let classes = booster.predict_classes(&[2.0, 2.0, 0.0, 0.0], 2, 2).unwrap();
+    /// assert_eq!(classes, vec![2, 0]);
+    /// ```
+    pub fn predict_classes(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<usize>> {
+        let objective = self.objective()?;
+        let threshold = match objective.as_str() {
+            "multi:softprob" => {
+                let probabilities = self.predict_typed(data, num_rows, num_features, 0, false)?;
+                return Ok(probabilities.argmax_per_row());
+            }
+            "multi:softmax" | "binary:hinge" => None,
+            "binary:logistic" => Some(0.5),
+            "binary:logitraw" => Some(0.0),
+            _ => {
+                return Err(XGBoostError::InvalidInput(format!(
+                    "Objective {} does not predict classes",
+                    objective
+                )))
+            }
+        };
+
+        let predictions = self.predict(data, num_rows, num_features, 0, false)?;
+        Ok(predictions
+            .into_iter()
+            .map(|prediction| match threshold {
+                Some(threshold) => usize::from(prediction > threshold),
+                None => prediction as usize,
+            })
+            .collect())
+    }
+
+    /// Make predictions on rows stored as separate slices
+    ///
+    /// The rows are copied into one contiguous buffer, and the number of rows and features
+    /// is taken from `rows`. Every row must have the same length.
+    ///
+    /// # Arguments
+    /// * `rows` - One slice of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let rows: Vec<Vec<f32>> = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+    /// let row_slices: Vec<&[f32]> = rows.iter().map(Vec::as_slice).collect();
+    /// let predictions = booster.predict_rows(&row_slices, 0, false).unwrap();
+    /// ```
+    pub fn predict_rows(
+        &self,
+        rows: &[&[f32]],
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let num_features = rows.first().map_or(0, |row| row.len());
+        if let Some((index, row)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != num_features)
+        {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Row {} has {} features, expected {} like the first row",
+                index,
+                row.len(),
+                num_features
+            )));
+        }
+
+        let data = rows.concat();
+        self.predict(&data, rows.len(), num_features, option_mask, training)
+    }
+
+    /// Make a prediction for a single row with a single-output model
+    ///
+    /// For regression and binary classification, where [`Booster::predict`] returns one
+    /// value per row, this returns that value directly. Models with several outputs per
+    /// row, such as multiclass models, give an error; use [`Booster::predict_typed`] for
+    /// those.
+    ///
+    /// # Arguments
+    /// * `row` - Features of the row
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// let prediction = booster.predict_one(&[3.0, 4.0]).unwrap();
+    /// assert_eq!(prediction, booster.predict(&[3.0, 4.0], 1, 2, 0, false).unwrap()[0]);
+    /// ```
+    pub fn predict_one(&self, row: &[f32]) -> XGBoostResult<f32> {
+        let predictions = self.predict(row, 1, row.len(), 0, false)?;
+        match predictions[..] {
+            [prediction] => Ok(prediction),
+            _ => Err(XGBoostError::InvalidInput(format!(
+                "Model predicts {} values per row, predict_one needs a single-output model",
+                predictions.len()
+            ))),
+        }
+    }
+
+    /// Make predictions on a range of rows of a larger row-major buffer
+    ///
+    /// Predicts on `buffer[row_range.start * num_features..row_range.end * num_features]`,
+    /// borrowing the rows instead of copying them out, e.g. for scoring sliding windows over
+    /// a preloaded feature matrix. Like [`Booster::predict`] with no options and `training`
+    /// set to false.
+    ///
+    /// # Arguments
+    /// * `buffer` - Row-major features of all rows, `num_features` values per row
+    /// * `row_range` - Rows to predict on, as row indices rather than offsets into `buffer`
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Returns
+    /// The predictions for the rows in `row_range`, in order. An empty range gives no
+    /// predictions, and a range past the end of `buffer` an error.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// // 4 rows of 2 features, scored in overlapping windows of 2 rows
+    /// let buffer = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    /// for start in 0..3 {
+    ///     let window = booster.predict_slice(&buffer, start..start + 2, 2).unwrap();
+    ///     assert_eq!(window.len(), 2);
+    /// }
+    /// assert!(booster.predict_slice(&buffer, 3..5, 2).is_err());
+    /// ```
+    pub fn predict_slice(
+        &self,
+        buffer: &[f32],
+        row_range: Range<usize>,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<f32>> {
+        let bounds = row_range
+            .start
+            .checked_mul(num_features)
+            .zip(row_range.end.checked_mul(num_features))
+            .filter(|(start, end)| start <= end && *end <= buffer.len());
+        let Some((start, end)) = bounds else {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Rows {:?} with {} features each are out of bounds for a buffer of {} values",
+                row_range,
+                num_features,
+                buffer.len()
+            )));
+        };
+        if row_range.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.predict(&buffer[start..end], row_range.len(), num_features, 0, false)
+    }
+
+    /// Make predictions on dense data, returning the shape of the predictions with them
+    ///
+    /// The predictions have one row per input row. The number of columns depends on the
+    /// model and `option_mask`, e.g. one per class for `multi:softprob` or one per feature
+    /// plus bias for `PRED_CONTRIBS`.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// The predictions (row-major) and their shape
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let (predictions, shape) = booster.predict_with_shape(&data, 2, 2, 0, false).unwrap();
+    /// for row in predictions.chunks(shape.cols) {
+    ///     println!("{:?}", row);
+    /// }
+    /// ```
+    pub fn predict_with_shape(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<(Vec<f32>, Shape)> {
+        let predictions = self.predict(data, num_rows, num_features, option_mask, training)?;
+        let shape = Shape {
+            rows: num_rows,
+            cols: predictions.len().checked_div(num_rows).unwrap_or(0),
+        };
+        Ok((predictions, shape))
+    }
+
+    /// Make predictions on dense data, keeping their shape and options with them
+    ///
+    /// Prefer this over [`Booster::predict`] when the predictions are reshaped or
+    /// interpreted later, since the number of values per row is kept with them.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("multiclass_model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_typed(&data, 2, 2, 0, false).unwrap();
+    /// for (row, class) in predictions.argmax_per_row().into_iter().enumerate() {
+    ///     println!("row {}: class {}", row, class);
+    /// }
+    /// ```
+    pub fn predict_typed(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Predictions> {
+        let predictions = self.predict(data, num_rows, num_features, option_mask, training)?;
+        Ok(Predictions::new(predictions, num_rows, option_mask))
+    }
+
+    /// Make predictions on dense `f64` data
+    ///
+    /// The data is converted to `f32` in a single pass before predicting. XGBoost stores
+    /// features and split thresholds as `f32`, so this gives the same predictions as
+    /// converting the data yourself: precision beyond `f32` is lost, and values outside
+    /// the `f32` range become infinite.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_f64(&data, 2, 2, 0, false).unwrap();
+    /// ```
+    pub fn predict_f64(
+        &self,
+        data: &[f64],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let data: Vec<f32> = data.iter().map(|&value| value as f32).collect();
+        self.predict(&data, num_rows, num_features, option_mask, training)
+    }
+
+    /// Make predictions on dense half-precision (`f16`) data
+    ///
+    /// The data is converted to `f32` in a single pass, using the CPU's conversion
+    /// instructions where available, and then predicted on like [`Booster::predict`].
+    /// Every `f16` is exactly representable as an `f32`, but the precision is that of the
+    /// source: about 3 significant decimal digits, with values up to 65504. A feature
+    /// close to a split threshold can fall on the other side of it after rounding to
+    /// `f16`, so predictions may differ from those on the original `f32` values. NaN
+    /// entries are treated as missing.
+    ///
+    /// Requires the `half` feature.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```
+    /// use half::f16;
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// // Small integers are exact in f16, so the predictions match the f32 ones
+    /// let data: Vec<f16> = [1.0, 2.0, 3.0, 4.0].iter().map(|&x| f16::from_f32(x)).collect();
+    /// assert_eq!(
+    ///     booster.predict_f16(&data, 2, 2, 0, false).unwrap(),
+    ///     booster.predict(&[1.0, 2.0, 3.0, 4.0], 2, 2, 0, false).unwrap()
+    /// );
+    /// ```
+    #[cfg(feature = "half")]
+    pub fn predict_f16(
+        &self,
+        data: &[half::f16],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        use half::slice::HalfFloatSliceExt;
+
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let mut converted = vec![0f32; data.len()];
+        data.convert_to_f32_slice(&mut converted);
+        self.predict(&converted, num_rows, num_features, option_mask, training)
+    }
+
+    /// Make predictions on dense `bf16` (bfloat16) data
+    ///
+    /// Like [`Booster::predict_f16`], for data stored as `bf16`. This has the range of an
+    /// `f32` but only about 2 to 3 significant decimal digits, so rounding to `bf16` moves
+    /// features across split thresholds more often than `f16` does for values in range.
+    ///
+    /// Requires the `half` feature.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use half::bf16;
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data: Vec<bf16> = [1.0, 2.0, 3.0, 4.0].iter().map(|&x| bf16::from_f32(x)).collect();
+    /// let predictions = booster.predict_bf16(&data, 2, 2, 0, false).unwrap();
+    /// ```
+    #[cfg(feature = "half")]
+    pub fn predict_bf16(
+        &self,
+        data: &[half::bf16],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        use half::slice::HalfFloatSliceExt;
+
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let mut converted = vec![0f32; data.len()];
+        data.convert_to_f32_slice(&mut converted);
+        self.predict(&converted, num_rows, num_features, option_mask, training)
+    }
+
+    /// Make predictions on dense column-major (Fortran order) data
+    ///
+    /// XGBoost reads the buffer directly through strides, so column-oriented data does not
+    /// need to be transposed first. Missing values are `f32::NAN`. Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (column-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// // 2 rows, 2 features: the first feature is [1.0, 2.0], the second [3.0, 4.0]
+    /// let data = vec![1.0, 2.0, 3.0, 4.0];
+    /// let predictions = booster.predict_colmajor(&data, 2, 2, 0, false).unwrap();
+    /// ```
+    #[cfg(xgboost_inplace_predict)]
+    pub fn predict_colmajor(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let array_interface = column_major_array_interface(data, num_rows, num_features);
+        let config = format!(r#"{{"missing": {}, "nthread": 0}}"#, json_f32(f32::NAN));
+        // The descriptor covers exactly `data`, whose length was checked above
+        let dmatrix = unsafe { DMatrix::from_array_interface(&array_interface, &config)? };
+        self.predict_dmatrix(&dmatrix, option_mask, training)
+    }
+
+    /// Run a throwaway prediction to prime XGBoost's internal caches
+    ///
+    /// The first prediction after loading pays one-time setup costs. Calling this at
+    /// startup moves that cost out of the first real request. It predicts a single row of
+    /// missing values and discards the result.
+    ///
+    /// # Arguments
+    /// * `num_features` - Number of features the model expects
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.warmup(booster.num_features().unwrap()).unwrap();
+    /// ```
+    pub fn warmup(&self, num_features: usize) -> XGBoostResult<()> {
+        let row = vec![f32::NAN; num_features];
+        self.predict(&row, 1, num_features, 0, false)?;
+        Ok(())
+    }
+
+    /// Predict the leaf index of every tree for each row
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Returns
+    /// One vector per row, holding the leaf index reached in each tree
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let leaves = booster.predict_leaf_indices(&data, 2, 2).unwrap();
+    /// println!("row 0 reached leaf {} in tree 0", leaves[0][0]);
+    /// ```
+    pub fn predict_leaf_indices(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<Vec<i32>>> {
+        if num_rows == 0 {
+            return Ok(Vec::new());
+        }
+
+        let predictions = self.predict(
+            data,
+            num_rows,
+            num_features,
+            crate::predict_option::PRED_LEAF,
+            false,
+        )?;
+        if predictions.len() % num_rows != 0 {
+            return Err(XGBoostError::Native(format!(
+                "XGBoost returned {} leaf indices, not a multiple of {} rows",
+                predictions.len(),
+                num_rows
+            )));
+        }
+
+        let num_trees = predictions.len() / num_rows;
+        if num_trees == 0 {
+            return Ok(vec![Vec::new(); num_rows]);
+        }
+        Ok(predictions
+            .chunks(num_trees)
+            .map(|row| row.iter().map(|&leaf| leaf as i32).collect())
+            .collect())
+    }
+
+    /// Make predictions with per-call options
+    ///
+    /// When `config.nthread` is set, the booster's `nthread` parameter is changed for this
+    /// prediction only and restored afterwards, even if the prediction fails.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `config` - Prediction options
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, PredictConfig};
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// let config = PredictConfig {
+    ///     nthread: Some(1),
+    ///     ..Default::default()
+    /// };
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.predict_with_config(&data, 2, 2, &config).unwrap();
+    /// ```
+    pub fn predict_with_config(
+        &mut self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        config: &PredictConfig,
+    ) -> XGBoostResult<Vec<f32>> {
+        let dmatrix = DMatrix::from_dense(data, num_rows, num_features, f32::NAN)?;
+        self.predict_dmatrix_with_config(&dmatrix, config)
+    }
+
+    /// Make predictions on an existing matrix with per-call options
+    ///
+    /// See [`Booster::predict_with_config`].
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Matrix to predict on
+    /// * `config` - Prediction options
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix, PredictConfig};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// // Predicting with the first 2 rounds matches a model trained for 2 rounds
+    /// let config = PredictConfig {
+    ///     iteration_end: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let limited = booster.predict_dmatrix_with_config(&dtrain, &config).unwrap();
+    /// let two_rounds = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    /// assert_eq!(limited, two_rounds.predict_dmatrix(&dtrain, 0, false).unwrap());
+    /// ```
+    pub fn predict_dmatrix_with_config(
+        &mut self,
+        dmatrix: &DMatrix,
+        config: &PredictConfig,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.warn_dart_training(config.training);
+        let ntree_limit = self.ntree_limit(config.iteration_end)?;
+        self.with_nthread(config.nthread, |booster| {
+            let mut predictions = Vec::new();
+            booster.predict_dmatrix_into(
+                dmatrix,
+                config.option_mask,
+                config.training,
+                ntree_limit,
+                &mut predictions,
+            )?;
+            Ok(predictions)
+        })
+    }
+
+    /// Make predictions on an existing matrix with per-call options, along with their shape
+    ///
+    /// The values are the same as from [`Booster::predict_dmatrix_with_config`], in
+    /// row-major order. XGBoost also reports their shape, which depends on the model and
+    /// the options: by default single outputs have shape `[rows]` and multiclass outputs
+    /// `[rows, classes]`. With `config.strict_shape`, the shape instead always has the
+    /// same dimensions for a kind of output, e.g. `[rows, groups]` for predictions and
+    /// `[rows, groups, features + 1]` for `PRED_CONTRIBS`, where `groups` is 1 except for
+    /// multiclass models. Requires XGBoost ≥ 1.4.
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Matrix to predict on
+    /// * `config` - Prediction options
+    ///
+    /// # Returns
+    /// The predictions and their shape
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix, PredictConfig};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut booster = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    ///
+    /// let (_, shape) = booster
+    ///     .predict_dmatrix_with_shape(&dtrain, &PredictConfig::default())
+    ///     .unwrap();
+    /// assert_eq!(shape, vec![2]);
+    ///
+    /// let config = PredictConfig {
+    ///     strict_shape: true,
+    ///     ..Default::default()
+    /// };
+    /// let (_, shape) = booster.predict_dmatrix_with_shape(&dtrain, &config).unwrap();
+    /// assert_eq!(shape, vec![2, 1]);
+    /// ```
+    #[cfg(xgboost_predict_from_dmatrix)]
+    pub fn predict_dmatrix_with_shape(
+        &mut self,
+        dmatrix: &DMatrix,
+        config: &PredictConfig,
+    ) -> XGBoostResult<(Vec<f32>, Vec<usize>)> {
+        use crate::predict_option::{
+            OUTPUT_MARGIN, PRED_APPROX_CONTRIBS, PRED_CONTRIBS, PRED_INTERACTIONS, PRED_LEAF,
+        };
+
+        config.validate()?;
+        self.warn_dart_training(config.training);
+        let approx = config.option_mask & PRED_APPROX_CONTRIBS != 0;
+        let prediction_type = if config.option_mask & PRED_LEAF != 0 {
+            6
+        } else if config.option_mask & PRED_INTERACTIONS != 0 {
+            if approx {
+                5
+            } else {
+                4
+            }
+        } else if config.option_mask & PRED_CONTRIBS != 0 {
+            if approx {
+                3
+            } else {
+                2
+            }
+        } else if config.option_mask & OUTPUT_MARGIN != 0 {
+            1
+        } else {
+            0
+        };
+        let json_config = format!(
+            r#"{{"type": {}, "training": {}, "iteration_begin": 0, "iteration_end": {}, "strict_shape": {}}}"#,
+            prediction_type,
+            config.training,
+            self.check_iteration_end(config.iteration_end)?,
+            config.strict_shape
+        );
+        let config_c_str = CString::new(json_config)?;
+
+        self.with_nthread(config.nthread, |booster| {
+            let mut out_shape: *const u64 = ptr::null();
+            let mut out_dim: u64 = 0;
+            let mut out_result: *const f32 = ptr::null();
+
+            XGBoostError::check_return_value(unsafe {
+                sys::XGBoosterPredictFromDMatrix(
+                    booster.handle,
+                    dmatrix.handle(),
+                    config_c_str.as_ptr(),
+                    &mut out_shape,
+                    &mut out_dim,
+                    &mut out_result,
+                )
+            })?;
+
+            unsafe { copy_shaped_result(out_shape, out_dim, out_result) }
+        })
+    }
+
+    /// Run `f` with the booster's `nthread` parameter set to `nthread`, if given, and
+    /// restore the previous setting afterwards, even if `f` fails
+    fn with_nthread<T>(
+        &mut self,
+        nthread: Option<usize>,
+        f: impl FnOnce(&Self) -> XGBoostResult<T>,
+    ) -> XGBoostResult<T> {
+        let Some(nthread) = nthread else {
+            return f(self);
+        };
+
+        let previous_nthread = json::lookup(
+            &self.save_config()?,
+            &["learner", "generic_param", "nthread"],
+        )?
+        .unwrap_or_else(|| "0".to_owned());
+
+        self.set_param("nthread", &nthread.to_string())?;
+        let result = f(self);
+        let restored = self.set_param("nthread", &previous_nthread);
+
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    /// Check `iteration_end` against the boosting rounds of the model, returning 0 for
+    /// every round
+    fn check_iteration_end(&self, iteration_end: Option<usize>) -> XGBoostResult<usize> {
+        let Some(rounds) = iteration_end.filter(|&rounds| rounds > 0) else {
+            return Ok(0);
+        };
+        // dart does not check the limit against its trees, so check it here
+        let num_rounds = self.num_boosted_rounds()?;
+        if rounds > num_rounds {
+            return Err(XGBoostError::InvalidInput(format!(
+                "iteration_end {} exceeds the {} boosting rounds of the model",
+                rounds, num_rounds
+            )));
+        }
+        Ok(rounds)
+    }
+
+    /// Convert a number of boosting rounds into the `ntree_limit` of `XGBoosterPredict`,
+    /// which counts every tree of a round's forest (`num_parallel_tree`)
+    fn ntree_limit(&self, iteration_end: Option<usize>) -> XGBoostResult<u32> {
+        let rounds = self.check_iteration_end(iteration_end)?;
+        if rounds == 0 {
+            return Ok(0);
+        }
+
+        // The parameter moved between XGBoost versions, and dart nests its gbtree config
+        const PATHS: [&[&str]; 4] = [
+            &[
+                "learner",
+                "gradient_booster",
+                "gbtree_model_param",
+                "num_parallel_tree",
+            ],
+            &[
+                "learner",
+                "gradient_booster",
+                "gbtree_train_param",
+                "num_parallel_tree",
+            ],
+            &[
+                "learner",
+                "gradient_booster",
+                "gbtree",
+                "gbtree_model_param",
+                "num_parallel_tree",
+            ],
+            &[
+                "learner",
+                "gradient_booster",
+                "gbtree",
+                "gbtree_train_param",
+                "num_parallel_tree",
+            ],
+        ];
+        let config = self.save_config()?;
+        let mut num_parallel_tree = None;
+        for path in PATHS {
+            num_parallel_tree = json::lookup(&config, path)?;
+            if num_parallel_tree.is_some() {
+                break;
+            }
+        }
+        let num_parallel_tree: usize = match num_parallel_tree {
+            Some(value) => value.trim().parse().map_err(|_| {
+                XGBoostError::Native(format!(
+                    "Invalid num_parallel_tree {:?} in model config",
+                    value
+                ))
+            })?,
+            None => 1,
+        };
+
+        rounds
+            .checked_mul(num_parallel_tree.max(1))
+            .and_then(|trees| u32::try_from(trees).ok())
+            .ok_or_else(|| {
+                XGBoostError::InvalidInput(format!("iteration_end {} is too large", rounds))
+            })
+    }
+
+    /// Predict SHAP interaction values, reshaped into one matrix per row
+    ///
+    /// Each row's matrix is `(num_features + 1) x (num_features + 1)`: entry `[i][j]` is
+    /// the interaction between features `i` and `j`, and the last row and column hold the
+    /// bias. Summing a row of the matrix gives that feature's SHAP contribution.
+    /// Only single-output models are supported; for multiclass models use
+    /// [`Booster::predict`] with `predict_option::PRED_INTERACTIONS`.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::predict_option::PRED_CONTRIBS;
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let data = [1.0, 5.0, 2.0, 3.0, 3.0, 1.0, 4.0, 4.0, 5.0, 2.0, 6.0, 6.0];
+    /// let mut dtrain = DMatrix::from_dense(&data, 6, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[1.0, 0.5, 3.0, 2.5, 4.0, 6.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .param("min_child_weight", "0")
+    ///     .num_round(5)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let interactions = booster.predict_interactions(&data, 6, 2).unwrap();
+    /// println!("row 0, features 0 x 1: {}", interactions[0][0][1]);
+    ///
+    /// // Each row of a matrix sums to that feature's (or the bias's) SHAP contribution
+    /// let contribs = booster.predict(&data, 6, 2, PRED_CONTRIBS, false).unwrap();
+    /// for (matrix, row_contribs) in interactions.iter().zip(contribs.chunks(3)) {
+    ///     for (interaction_row, &contrib) in matrix.iter().zip(row_contribs) {
+    ///         let sum: f32 = interaction_row.iter().sum();
+    ///         assert!((sum - contrib).abs() < 1e-4, "{} != {}", sum, contrib);
+    ///     }
+    /// }
+    /// ```
+    pub fn predict_interactions(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+    ) -> XGBoostResult<Vec<Vec<Vec<f32>>>> {
+        if num_rows == 0 {
+            return Ok(Vec::new());
+        }
+
+        let predictions = self.predict(
+            data,
+            num_rows,
+            num_features,
+            crate::predict_option::PRED_INTERACTIONS,
+            false,
+        )?;
+
+        let side = num_features + 1;
+        let expected = num_rows * side * side;
+        if predictions.len() != expected {
+            return Err(XGBoostError::InvalidInput(format!(
+                "predict_interactions only supports single-output models: expected {} values, \
+                 got {}",
+                expected,
+                predictions.len()
+            )));
+        }
+
+        Ok(predictions
+            .chunks(side * side)
+            .map(|matrix| matrix.chunks(side).map(<[f32]>::to_vec).collect())
+            .collect())
+    }
+
+    /// Make predictions on an existing matrix
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Matrix to predict on
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// let predictions = booster.predict_dmatrix(&dmatrix, 0, false).unwrap();
+    /// ```
+    pub fn predict_dmatrix(
+        &self,
+        dmatrix: &DMatrix,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        self.warn_dart_training(training);
+        let mut results = Vec::new();
+        self.predict_dmatrix_into(dmatrix, option_mask, training, 0, &mut results)?;
+        Ok(results)
+    }
+
+    /// Check that the base margin of a matrix has one value per row and model output
+    ///
+    /// [`DMatrix::set_base_margin`] only knows the number of rows, so it accepts any
+    /// whole number of margins per row. This checks the margin against the model too: it
+    /// must have `num_row * num_class` values, e.g. one per class for a multiclass model.
+    /// A matrix without a base margin passes.
+    ///
+    /// # Arguments
+    /// * `dmatrix` - Matrix that will be predicted on
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .objective("binary:logistic")
+    ///     .num_round(2)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let mut dmatrix = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dmatrix.set_base_margin(&[0.3, -1.2, 0.5, 0.1]).unwrap(); // two per row
+    /// assert!(booster.check_base_margin(&dmatrix).is_err());
+    ///
+    /// dmatrix.set_base_margin(&[0.3, -1.2]).unwrap();
+    /// booster.check_base_margin(&dmatrix).unwrap();
+    /// let predictions = booster.predict_dmatrix(&dmatrix, 0, false).unwrap();
+    /// ```
+    pub fn check_base_margin(&self, dmatrix: &DMatrix) -> XGBoostResult<()> {
+        let margin = dmatrix.get_float_info("base_margin")?;
+        if margin.is_empty() {
+            return Ok(());
+        }
+
+        let num_rows = dmatrix.num_row()?;
+        let num_class = self.num_class()?;
+        let expected = num_rows * num_class;
+        if margin.len() != expected {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Base margin has {} values, expected {} ({} rows x {} outputs of the model)",
+                margin.len(),
+                expected,
+                num_rows,
+                num_class
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Warn once per process when a dart model predicts with `training` set, since its
+    /// dropout then makes predictions vary between calls
+    ///
+    /// Reading the booster type takes a [`Booster::save_config`], so this only checks in
+    /// debug builds, where it catches the mistake during development, and only once per
+    /// booster until its parameters change.
+    fn warn_dart_training(&self, training: bool) {
+        static WARNED: Once = Once::new();
+        if !cfg!(debug_assertions) || !training || WARNED.is_completed() {
+            return;
+        }
+
+        let is_dart = *self.is_dart.get_or_init(|| {
+            // Only a warning, so a config that cannot be read is not an error here
+            let booster_name = self
+                .save_config()
+                .ok()
+                .and_then(|config| {
+                    json::lookup(&config, &["learner", "gradient_booster", "name"]).ok()
+                })
+                .flatten();
+            booster_name.as_deref() == Some("dart")
+        });
+        if is_dart {
+            WARNED.call_once(|| {
+                eprintln!(
+                    "warning: predicting with training = true applies dart dropout, so \
+                     predictions vary between calls; pass training = false for inference"
+                )
+            });
+        }
+    }
+
+    /// Predict on `dmatrix`, clearing `out` and filling it with the results
+    fn predict_dmatrix_into(
+        &self,
+        dmatrix: &DMatrix,
+        option_mask: u32,
+        training: bool,
+        ntree_limit: u32,
+        out: &mut Vec<f32>,
+    ) -> XGBoostResult<()> {
+        crate::predict_option::validate(option_mask)?;
+
+        let mut out_len: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterPredict(
+                self.handle,
+                dmatrix.handle(),
+                option_mask as i32,
+                ntree_limit, // 0 means use all trees
+                training as i32,
+                &mut out_len,
+                &mut out_result,
+            )
+        })?;
+
+        // An empty result may come with a null pointer, which must not become a slice
+        if out_len == 0 {
+            out.clear();
+            return Ok(());
+        }
+        if out_result.is_null() {
+            return Err(XGBoostError::Native(
+                "XGBoost returned null prediction result".to_string(),
+            ));
+        }
+
+        // Copy results into the output buffer
+        let results = unsafe { std::slice::from_raw_parts(out_result, out_len as usize) };
+        out.clear();
+        out.extend_from_slice(results);
+
+        Ok(())
+    }
+
+    /// Make predictions on a Tokio blocking thread, without blocking the async runtime
+    ///
+    /// Takes the booster as an `Arc` and the data by value, since the prediction runs on
+    /// another thread via `tokio::task::spawn_blocking`. Requires the `tokio` feature and
+    /// XGBoost ≥ 1.4, where `Booster` is `Send + Sync`.
+    ///
+    /// XGBoost's global configuration is per thread, so settings made with
+    /// [`set_global_config`](crate::set_global_config) on the calling task's thread do not
+    /// apply on the blocking thread; set them on the runtime's threads instead.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use xgboost_rust::{Booster, XGBoostResult};
+    ///
+    /// async fn handle_request(booster: Arc<Booster>, data: Vec<f32>) -> XGBoostResult<Vec<f32>> {
+    ///     booster.predict_async(data, 1, 4, 0, false).await
+    /// }
+    /// ```
+    #[cfg(all(feature = "tokio", xgboost_thread_safe))]
+    pub async fn predict_async(
+        self: std::sync::Arc<Self>,
+        data: Vec<f32>,
+        num_rows: usize,
+        num_features: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let task = tokio::task::spawn_blocking(move || {
+            self.predict(&data, num_rows, num_features, option_mask, training)
+        });
+
+        match task.await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(XGBoostError::Native(format!(
+                "Prediction task was cancelled: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Make predictions over a stream of rows, one batch at a time
+    ///
+    /// Rows are pulled lazily from `rows` and buffered until `batch_rows` are available
+    /// (or the input is exhausted), then predicted together. Results are yielded one row
+    /// at a time, so at most one batch of input and output is held in memory.
+    ///
+    /// The number of features is taken from the first row; a row with a different
+    /// length yields an error and ends the stream.
+    ///
+    /// # Arguments
+    /// * `rows` - Iterator of feature rows
+    /// * `batch_rows` - Maximum number of rows per prediction batch, greater than zero
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    ///
+    /// # Returns
+    /// An iterator yielding the prediction values for each input row, or
+    /// [`XGBoostError::InvalidInput`] if `batch_rows` is zero
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let rows = (0..1_000_000).map(|i| vec![i as f32, 1.0]);
+    /// for prediction in booster.predict_stream(rows, 1024, 0, false).unwrap() {
+    ///     let prediction = prediction.unwrap();
+    ///     println!("{:?}", prediction);
+    /// }
+    /// ```
+    ///
+    /// A batch size of zero is rejected rather than read as one row per batch:
+    ///
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix, XGBoostError};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    ///
+    /// let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+    /// let result = booster.predict_stream(rows.clone(), 0, 0, false);
+    /// assert!(matches!(result, Err(XGBoostError::InvalidInput(_))));
+    ///
+    /// let predictions: Vec<_> = booster.predict_stream(rows, 1, 0, false).unwrap().collect();
+    /// assert_eq!(predictions.len(), 2);
+    /// ```
+    pub fn predict_stream<'a, I>(
+        &'a self,
+        rows: I,
+        batch_rows: usize,
+        option_mask: u32,
+        training: bool,
+    ) -> XGBoostResult<impl Iterator<Item = XGBoostResult<Vec<f32>>> + 'a>
+    where
+        I: IntoIterator<Item = Vec<f32>>,
+        I::IntoIter: 'a,
+    {
+        if batch_rows == 0 {
+            return Err(XGBoostError::InvalidInput(
+                "batch_rows must be greater than zero".to_owned(),
+            ));
+        }
+
+        Ok(PredictStream {
+            booster: self,
+            rows: rows.into_iter(),
+            batch_rows,
+            option_mask,
+            training,
+            num_features: None,
+            batch: Vec::new(),
+            pending: VecDeque::new(),
+            done: false,
+        })
+    }
+
+    /// Make predictions batch by batch and write them to `writer` as they are produced
+    ///
+    /// Each item of `batches` holds one or more rows of `num_features` values (row-major).
+    /// The predictions of each batch are written as little-endian `f32` values before the
+    /// next batch is read, so at most one batch of input and output is held in memory. Use
+    /// [`read_predictions`] to read them back.
+    ///
+    /// # Arguments
+    /// * `batches` - Row batches to predict
+    /// * `num_features` - Number of features per row
+    /// * `writer` - Destination of the predictions
+    ///
+    /// # Returns
+    /// The number of prediction values written
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::BufWriter;
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![0.0f32; 4 * 1_000_000];
+    /// let mut writer = BufWriter::new(File::create("predictions.bin").unwrap());
+    /// booster
+    ///     .predict_to_writer(data.chunks(4 * 10_000), 4, &mut writer)
+    ///     .unwrap();
+    /// ```
+    pub fn predict_to_writer<'a, I, W>(
+        &self,
+        batches: I,
+        num_features: usize,
+        writer: &mut W,
+    ) -> XGBoostResult<usize>
+    where
+        I: IntoIterator<Item = &'a [f32]>,
+        W: Write,
+    {
+        if num_features == 0 {
+            return Err(XGBoostError::InvalidInput(
+                "num_features must be greater than zero".to_owned(),
+            ));
+        }
+
+        let mut num_written = 0;
+        let mut bytes = Vec::new();
+        for batch in batches {
+            if batch.len() % num_features != 0 {
+                return Err(XGBoostError::InvalidInput(format!(
+                    "Batch of {} values is not a whole number of rows of {} features",
+                    batch.len(),
+                    num_features
+                )));
+            }
+            let num_rows = batch.len() / num_features;
+            if num_rows == 0 {
+                continue;
+            }
+
+            let predictions = self.predict(batch, num_rows, num_features, 0, false)?;
+            bytes.clear();
+            bytes.extend(predictions.iter().flat_map(|value| value.to_le_bytes()));
+            writer
+                .write_all(&bytes)
+                .map_err(|e| XGBoostError::Io(format!("Failed to write predictions: {}", e)))?;
+            num_written += predictions.len();
+        }
+
+        writer
+            .flush()
+            .map_err(|e| XGBoostError::Io(format!("Failed to write predictions: {}", e)))?;
+
+        Ok(num_written)
+    }
+
+    /// Make predictions directly on a dense row-major buffer, without building a DMatrix
+    ///
+    /// This uses XGBoost's inplace prediction, which skips the DMatrix allocation done by
+    /// [`Booster::predict`] and is noticeably faster for small batches.
+    /// Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `data` - 2D array of features (row-major, num_rows x num_features)
+    /// * `num_rows` - Number of rows in the data
+    /// * `num_features` - Number of features per row
+    /// * `missing` - Value to treat as missing (usually `f32::NAN`)
+    ///
+    /// # Returns
+    /// A vector of prediction values
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let predictions = booster.inplace_predict(&data, 2, 2, f32::NAN).unwrap();
+    /// ```
+    #[cfg(xgboost_inplace_predict)]
+    pub fn inplace_predict(
+        &self,
+        data: &[f32],
+        num_rows: usize,
+        num_features: usize,
+        missing: f32,
+    ) -> XGBoostResult<Vec<f32>> {
+        validate_dense_shape(data.len(), num_rows, num_features)?;
+
+        let array_interface = dense_array_interface(data, num_rows, num_features);
+        let array_interface_c_str = CString::new(array_interface).unwrap();
+        let config = format!(
+            r#"{{"type": 0, "training": false, "iteration_begin": 0, "iteration_end": 0, "strict_shape": false, "cache_id": 0, "missing": {}}}"#,
+            json_f32(missing)
+        );
+        let config_c_str = CString::new(config).unwrap();
+
+        let mut out_shape: *const u64 = ptr::null();
+        let mut out_dim: u64 = 0;
+        let mut out_result: *const f32 = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterPredictFromDense(
+                self.handle,
+                array_interface_c_str.as_ptr(),
+                config_c_str.as_ptr(),
+                ptr::null_mut(), // no proxy DMatrix
+                &mut out_shape,
+                &mut out_dim,
+                &mut out_result,
+            )
+        })?;
+
+        let (results, _shape) = unsafe { copy_shaped_result(out_shape, out_dim, out_result)? };
+        Ok(results)
+    }
+
+    /// Get the number of features the model expects
+    ///
+    /// # Returns
+    /// The number of features
+    pub fn num_features(&self) -> XGBoostResult<usize> {
+        let mut out_num_features: u64 = 0;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetNumFeature(self.handle, &mut out_num_features)
+        })?;
+
+        Ok(out_num_features as usize)
+    }
+
+    /// Get the number of classes of a multiclass model
+    ///
+    /// Read from the `num_class` learner parameter in [`Booster::save_config`]. This is the
+    /// number of prediction columns for `multi:softprob`; `multi:softmax` still predicts
+    /// a single class index per row.
+    ///
+    /// # Returns
+    /// The number of classes, or 1 for regression and binary models
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let data = vec![1.0, 2.0, 3.0, 4.0]; // 2 rows, 2 features
+    /// let probabilities = booster.predict_proba(&data, 2, 2).unwrap();
+    /// for row in probabilities.chunks(booster.num_class().unwrap()) {
+    ///     println!("{:?}", row);
+    /// }
+    /// ```
+    pub fn num_class(&self) -> XGBoostResult<usize> {
+        let num_class: usize = config_number(
+            &self.save_config()?,
+            &["learner", "learner_model_param", "num_class"],
+        )?;
+        Ok(num_class.max(1))
+    }
+
+    /// Get the name of the model's objective, e.g. `binary:logistic` or `multi:softprob`
+    ///
+    /// Read from the `objective` learner parameter in [`Booster::save_config`].
+    pub fn objective(&self) -> XGBoostResult<String> {
+        config_entry(&self.save_config()?, &["learner", "objective", "name"])
+    }
+
+    /// Describe the model: its features, classes, objective, booster and base score
+    ///
+    /// Reads everything from a single [`Booster::save_config`], so this is cheaper than
+    /// calling [`Booster::num_class`], [`Booster::objective`] and the others one by one.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .objective("binary:logistic")
+    ///     .num_round(2)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let metadata = booster.model_metadata().unwrap();
+    /// assert_eq!(metadata.num_features, 2);
+    /// assert_eq!(metadata.num_class, 1);
+    /// assert_eq!(metadata.objective, "binary:logistic");
+    /// assert_eq!(metadata.booster_type, "gbtree");
+    /// assert_eq!(metadata.base_score.len(), 1);
+    /// ```
+    pub fn model_metadata(&self) -> XGBoostResult<ModelMetadata> {
+        let config = self.save_config()?;
+        let num_class: usize =
+            config_number(&config, &["learner", "learner_model_param", "num_class"])?;
+
+        let base_score = config_base_score(&config)?;
+
+        Ok(ModelMetadata {
+            num_features: config_number(
+                &config,
+                &["learner", "learner_model_param", "num_feature"],
+            )?,
+            num_class: num_class.max(1),
+            objective: config_entry(&config, &["learner", "objective", "name"])?,
+            booster_type: config_entry(&config, &["learner", "gradient_booster", "name"])?,
+            base_score,
+        })
+    }
+
+    /// Get the model's base score, the global bias every prediction starts from
+    ///
+    /// Read from `learner.learner_model_param.base_score` in [`Booster::save_config`]. The
+    /// value is on the scale of the predictions, not of the margins: a margin from
+    /// [`Booster::predict_margin`] starts from the base score passed through the
+    /// objective's link function, e.g. `ln(p / (1 - p))` for `binary:logistic`, `ln(p)`
+    /// for `count:poisson`, `reg:gamma` and `reg:tweedie`, and the base score itself for
+    /// `reg:squarederror`.
+    ///
+    /// Unless `base_score` is set when training, XGBoost < 2.0 uses 0.5, while XGBoost
+    /// ≥ 2.0 estimates it from the training labels.
+    ///
+    /// # Errors
+    /// Returns an error if the model has a base score per output, as multi-output models
+    /// can in newer versions of XGBoost; [`Booster::model_metadata`] returns all of them.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new()
+    ///     .objective("binary:logistic")
+    ///     .param("base_score", "0.2")
+    ///     .param("eta", "0")
+    ///     .num_round(1)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// // With a learning rate of 0 the trees add nothing, leaving the base score's logit
+    /// let base_score = booster.base_score().unwrap();
+    /// let margin = booster.predict_margin(&[1.0, 2.0], 1, 2).unwrap()[0];
+    /// assert_eq!(base_score, 0.2);
+    /// assert!((margin - (base_score / (1.0 - base_score)).ln()).abs() < 1e-5);
+    /// ```
+    pub fn base_score(&self) -> XGBoostResult<f32> {
+        match config_base_score(&self.save_config()?)?[..] {
+            [base_score] => Ok(base_score),
+            ref base_scores => Err(XGBoostError::Native(format!(
+                "Model has {} base scores, one per output; use Booster::model_metadata to read them",
+                base_scores.len()
+            ))),
+        }
+    }
+
+    /// Get the number of boosting rounds the model has been trained for
+    pub fn num_boosted_rounds(&self) -> XGBoostResult<usize> {
+        let mut out_rounds: i32 = 0;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterBoostedRounds(self.handle, &mut out_rounds)
+        })?;
+
+        Ok(out_rounds as usize)
+    }
+
+    /// Set a booster parameter
+    ///
+    /// Parameters that only affect how the model is applied, such as `nthread`, `device`
+    /// (XGBoost ≥ 2.0) or `predictor` (XGBoost < 2.0), can be changed at any time after
+    /// loading. Parameters that define the model itself, such as `booster`, `objective` or
+    /// `num_class`, must match the loaded model; changing them requires training a new model.
+    ///
+    /// # Arguments
+    /// * `name` - Parameter name, e.g. `max_depth`
+    /// * `value` - Parameter value, e.g. `6`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_param("eta", "0.1").unwrap();
+    /// ```
+    pub fn set_param(&mut self, name: &str, value: &str) -> XGBoostResult<()> {
+        let name_c_str = CString::new(name)?;
+        let value_c_str = CString::new(value)?;
+
+        self.is_dart = OnceLock::new();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSetParam(self.handle, name_c_str.as_ptr(), value_c_str.as_ptr())
+        })
+    }
+
+    /// Set several booster parameters, all or none
+    ///
+    /// XGBoost only checks parameter values when the booster is next configured, so a bad
+    /// value passed to [`Booster::set_param`] surfaces later, e.g. from
+    /// [`Booster::train`]. This method configures the booster after each parameter and
+    /// stops at the first invalid one, returning an error naming it. The parameters already
+    /// applied are then set back to their previous values, as read from
+    /// [`Booster::save_config`], and the booster is left usable. Parameters the
+    /// configuration does not report, such as `eval_metric`, are rolled back by reloading
+    /// the whole configuration with [`Booster::load_config`]. If rolling back fails too,
+    /// the error still names the invalid parameter, followed by what could not be restored.
+    ///
+    /// Configuring needs the number of features, so the booster must hold a model or have
+    /// been created with training data.
+    ///
+    /// # Arguments
+    /// * `params` - Parameter names and values, applied in order
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{Booster, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut booster = Booster::new(&[&dtrain]).unwrap();
+    ///
+    /// booster.set_params(&[("eta", "0.1"), ("max_depth", "4")]).unwrap();
+    ///
+    /// let error = booster
+    ///     .set_params(&[("eta", "0.5"), ("max_depth", "deep")])
+    ///     .unwrap_err();
+    /// assert!(error.to_string().contains("max_depth"));
+    /// assert!(booster.save_config().unwrap().contains(r#""max_depth":"4""#));
+    ///
+    /// // Not in the configuration, so rolled back by reloading it
+    /// let error = booster.set_params(&[("eval_metric", "bogus")]).unwrap_err();
+    /// assert!(error.to_string().contains("eval_metric"));
+    /// booster.update_one_iter(0, &dtrain).unwrap();
+    /// ```
+    pub fn set_params(&mut self, params: &[(&str, &str)]) -> XGBoostResult<()> {
+        let snapshot = self.save_config()?;
+
+        for (applied, &(name, value)) in params.iter().enumerate() {
+            let result = self
+                .set_param(name, value)
+                .and_then(|()| self.save_config().map(drop));
+            if let Err(error) = result {
+                let rollback_failures = self.roll_back_params(&snapshot, &params[..=applied]);
+                return Err(match error {
+                    XGBoostError::Native(message) if rollback_failures.is_empty() => {
+                        XGBoostError::Native(format!(
+                            "Invalid value for parameter {}: {}",
+                            name, message
+                        ))
+                    }
+                    XGBoostError::Native(message) => XGBoostError::Native(format!(
+                        "Invalid value for parameter {}: {} (rolling back also failed: {})",
+                        name,
+                        message,
+                        rollback_failures.join("; ")
+                    )),
+                    error => error,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `params` back to their values in the `snapshot` config, for [`Booster::set_params`]
+    ///
+    /// Parameters the config does not report, such as `eval_metric`, cannot be set back one
+    /// by one, so the whole snapshot is reloaded for those. Returns the steps that failed,
+    /// leaving the error that caused the rollback to the caller.
+    fn roll_back_params(&mut self, snapshot: &str, params: &[(&str, &str)]) -> Vec<String> {
+        let mut failures = Vec::new();
+        let mut reload = false;
+        for &(name, _) in params {
+            match json::find(snapshot, name) {
+                Ok(Some(previous)) => {
+                    if let Err(e) = self.set_param(name, &previous) {
+                        failures.push(format!("restoring {}: {}", name, e));
+                    }
+                }
+                Ok(None) => reload = true,
+                Err(e) => {
+                    failures.push(format!("reading {} from the configuration: {}", name, e));
+                    reload = true;
+                }
+            }
+        }
+        if reload {
+            if let Err(e) = self.load_config(snapshot) {
+                failures.push(format!("reloading the configuration: {}", e));
+            }
+        }
+        failures
+    }
+
+    /// Select the device used for prediction and training, e.g. `cpu`, `cuda` or `cuda:0`
+    ///
+    /// This sets the `device` parameter, which [`Booster::predict`] and the other
+    /// prediction methods then use. GPU devices need the CUDA build of XGBoost; if the linked
+    /// library was built without CUDA, an error is returned instead of silently falling back
+    /// to the CPU. Requires XGBoost ≥ 2.0.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.use_device("cuda:0").unwrap();
+    /// let predictions = booster.predict(&[1.0, 2.0, 3.0, 4.0], 2, 2, 0, false).unwrap();
+    /// ```
+    #[cfg(xgboost_device)]
+    pub fn use_device(&mut self, device: &str) -> XGBoostResult<()> {
+        let wants_gpu = device.starts_with("cuda") || device.starts_with("gpu");
+        if wants_gpu && !crate::global::xgboost_has_cuda()? {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Cannot use device '{}': the linked XGBoost library was built without CUDA support",
+                device
+            )));
+        }
+
+        self.set_param("device", device)
+    }
+
+    /// Reset the booster, releasing its prediction and training caches
+    ///
+    /// The trees and parameters are kept; only cached data derived from matrices the
+    /// booster has seen is dropped. Useful after changing parameters such as `device` with
+    /// [`Booster::set_param`]. Requires XGBoost ≥ 2.0.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// booster.set_param("device", "cuda:0").unwrap();
+    /// booster.reset().unwrap();
+    /// ```
+    #[cfg(xgboost_reset)]
+    pub fn reset(&mut self) -> XGBoostResult<()> {
+        XGBoostError::check_return_value(unsafe { sys::XGBoosterReset(self.handle) })
+    }
+
+    /// Run one boosting iteration on a training matrix
+    ///
+    /// # Arguments
+    /// * `iteration` - Current iteration number, starting from 0
+    /// * `dtrain` - Training data, with labels set
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    ///
+    /// let mut booster = BoosterBuilder::new().build(&[&dtrain]).unwrap();
+    /// for iteration in 0..10 {
+    ///     booster.update_one_iter(iteration, &dtrain).unwrap();
+    /// }
+    /// ```
+    ///
+    /// # Continuing training
+    /// A loaded model can be trained further, e.g. on new data: each iteration adds trees
+    /// on top of the existing ones. Number the iterations from
+    /// [`Booster::num_boosted_rounds`], as [`Booster::train`] does.
+    ///
+    /// ```
+    /// use xgboost_rust::{Booster, BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let path = std::env::temp_dir().join("xgboost_rust_continue_training.json");
+    /// BoosterBuilder::new().num_round(2).train(&dtrain).unwrap().save(&path).unwrap();
+    ///
+    /// let mut booster = Booster::load(&path).unwrap();
+    /// let before = booster.predict(&[1.0, 2.0], 1, 2, 0, false).unwrap();
+    /// let mut dnew = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dnew.set_labels(&[0.5, 1.5]).unwrap();
+    /// let rounds = booster.num_boosted_rounds().unwrap();
+    /// booster.update_one_iter(rounds as i32, &dnew).unwrap();
+    ///
+    /// assert_eq!(booster.num_boosted_rounds().unwrap(), 3);
+    /// assert_ne!(booster.predict(&[1.0, 2.0], 1, 2, 0, false).unwrap(), before);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn update_one_iter(&mut self, iteration: i32, dtrain: &DMatrix) -> XGBoostResult<()> {
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterUpdateOneIter(self.handle, iteration, dtrain.handle())
+        })
+    }
+
+    /// Run one boosting iteration with gradients computed by the caller
+    ///
+    /// This is how custom objectives are trained: compute the first and second order
+    /// gradients of the loss for the current predictions (see
+    /// [`Booster::train_with_objective`]) and pass them here instead of calling
+    /// [`Booster::update_one_iter`].
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data
+    /// * `grad` - Gradient of the loss for each prediction
+    /// * `hess` - Second order gradient (hessian) of the loss for each prediction
+    ///
+    /// Both slices have one value per row, or one per row and output for multi-output
+    /// models such as `multi:softprob`, in the same layout as the predictions.
+    pub fn boost_one_iter(
+        &mut self,
+        dtrain: &DMatrix,
+        grad: &[f32],
+        hess: &[f32],
+    ) -> XGBoostResult<()> {
+        if grad.len() != hess.len() {
+            return Err(XGBoostError::DimensionMismatch {
+                expected: grad.len(),
+                got: hess.len(),
+            });
+        }
+        let num_rows = dtrain.num_row()?;
+        if grad.is_empty() || num_rows == 0 || grad.len() % num_rows != 0 {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Expected a non-zero multiple of {} gradients (one per row and output), got {}",
+                num_rows,
+                grad.len()
+            )));
+        }
+
+        // XGBoost only reads the gradients, despite the mutable pointers
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterBoostOneIter(
+                self.handle,
+                dtrain.handle(),
+                grad.as_ptr() as *mut f32,
+                hess.as_ptr() as *mut f32,
+                grad.len() as u64,
+            )
+        })
+    }
+
+    /// Train for a number of boosting rounds with a custom objective
+    ///
+    /// Each round, `objective` is called with the current untransformed predictions
+    /// (margins) on `dtrain` and `dtrain` itself, and returns the gradients and hessians
+    /// of the loss, which are passed to [`Booster::boost_one_iter`].
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data, with whatever information the objective needs set
+    /// * `rounds` - Number of boosting rounds to run
+    /// * `objective` - Computes `(grad, hess)` from the predictions and training data
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    ///
+    /// // Squared error: grad = prediction - label, hess = 1
+    /// let mut booster = BoosterBuilder::new().build(&[&dtrain]).unwrap();
+    /// booster
+    ///     .train_with_objective(&dtrain, 10, |predictions, dtrain| {
+    ///         let labels = dtrain.get_float_info("label").unwrap();
+    ///         let grad = predictions.iter().zip(&labels).map(|(p, l)| p - l).collect();
+    ///         let hess = vec![1.0; predictions.len()];
+    ///         (grad, hess)
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn train_with_objective<F>(
+        &mut self,
+        dtrain: &DMatrix,
+        rounds: usize,
+        mut objective: F,
+    ) -> XGBoostResult<()>
+    where
+        F: FnMut(&[f32], &DMatrix) -> (Vec<f32>, Vec<f32>),
+    {
+        let mut predictions = Vec::new();
+        for _ in 0..rounds {
+            self.predict_dmatrix_into(
+                dtrain,
+                crate::predict_option::OUTPUT_MARGIN,
+                true,
+                0,
+                &mut predictions,
+            )?;
+            let (grad, hess) = objective(&predictions, dtrain);
+            self.boost_one_iter(dtrain, &grad, &hess)?;
+        }
+        Ok(())
+    }
+
+    /// Train for a number of boosting rounds, recording evaluation metrics after each one
+    ///
+    /// Rounds are numbered on from [`Booster::num_boosted_rounds`], so training a booster
+    /// that already has trees continues from where it left off. After each round the
+    /// model is evaluated on `evals` with [`Booster::eval_set`]; when `evals` is empty,
+    /// nothing is evaluated and the returned history is empty.
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data, with labels set
+    /// * `rounds` - Number of boosting rounds to run
+    /// * `evals` - Pairs of matrix and the name to report its metrics under
+    ///
+    /// # Returns
+    /// The metrics of every round
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut dvalid = DMatrix::from_dense(&[1.5, 2.5, 3.5, 4.5], 2, 2, f32::NAN).unwrap();
+    /// dvalid.set_labels(&[0.0, 1.0]).unwrap();
+    ///
+    /// let mut booster = BoosterBuilder::new().build(&[&dtrain, &dvalid]).unwrap();
+    /// let history = booster
+    ///     .train(&dtrain, 100, &[(&dtrain, "train"), (&dvalid, "valid")])
+    ///     .unwrap();
+    /// for (iteration, rmse) in history.metric("valid-rmse") {
+    ///     println!("{}: {}", iteration, rmse);
+    /// }
+    /// ```
+    pub fn train(
+        &mut self,
+        dtrain: &DMatrix,
+        rounds: usize,
+        evals: &[(&DMatrix, &str)],
+    ) -> XGBoostResult<TrainingHistory> {
+        let first_iteration = self.num_boosted_rounds()? as i32;
+        let mut history = TrainingHistory::new();
+
+        for iteration in first_iteration..first_iteration + rounds as i32 {
+            self.update_one_iter(iteration, dtrain)?;
+            if !evals.is_empty() {
+                let result = self.eval_set(evals, iteration)?;
+                history.record(iteration, parse_eval_result(&result));
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Train until the evaluation metric stops improving, or for at most `max_rounds` rounds
+    ///
+    /// After each round the model is evaluated on `evals`, and the metric that is reported
+    /// last (the last metric of the last matrix) is monitored, as in the Python package.
+    /// Training stops once it has not improved for `early_stopping_rounds` rounds. The best
+    /// iteration and its score are stored in the `best_iteration` and `best_score`
+    /// attributes of the model.
+    ///
+    /// Metrics where higher is better (`auc`, `aucpr`, `map`, `ndcg` and `pre`, including
+    /// their `@` variants) are maximized automatically; set `maximize` to maximize any
+    /// other metric.
+    ///
+    /// Trees from the rounds after the best iteration stay in the model, so predictions
+    /// that should use only the best model need to be limited to `best_iteration + 1`
+    /// rounds.
+    ///
+    /// # Arguments
+    /// * `dtrain` - Training data, with labels set
+    /// * `max_rounds` - Maximum number of boosting rounds to run
+    /// * `evals` - Pairs of matrix and the name to report its metrics under, with the
+    ///   monitored matrix last
+    /// * `early_stopping_rounds` - Number of rounds without improvement before stopping,
+    ///   at least 1. Zero is rejected with [`XGBoostError::InvalidInput`] rather than read
+    ///   as "no early stopping"; train with [`Booster::update_one_iter`] for that.
+    /// * `maximize` - Whether higher values of the monitored metric are better
+    ///
+    /// # Returns
+    /// The best iteration
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix, XGBoostError};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// // The validation labels are the other way round, so every round makes it worse
+    /// let mut dvalid = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dvalid.set_labels(&[1.0, 0.0]).unwrap();
+    ///
+    /// let mut booster = BoosterBuilder::new().build(&[&dtrain, &dvalid]).unwrap();
+    /// let best_iteration = booster
+    ///     .train_with_early_stopping(&dtrain, 100, &[(&dvalid, "valid")], 3, false)
+    ///     .unwrap();
+    /// assert_eq!(best_iteration, 0);
+    /// assert_eq!(booster.num_boosted_rounds().unwrap(), 4);
+    /// assert_eq!(booster.get_attr("best_iteration").unwrap().as_deref(), Some("0"));
+    /// let best_score: f32 = booster.get_attr("best_score").unwrap().unwrap().parse().unwrap();
+    /// assert!(best_score > 0.5);
+    ///
+    /// let zero = booster.train_with_early_stopping(&dtrain, 100, &[(&dvalid, "valid")], 0, false);
+    /// assert!(matches!(zero, Err(XGBoostError::InvalidInput(_))));
+    /// ```
+    pub fn train_with_early_stopping(
+        &mut self,
+        dtrain: &DMatrix,
+        max_rounds: usize,
+        evals: &[(&DMatrix, &str)],
+        early_stopping_rounds: usize,
+        maximize: bool,
+    ) -> XGBoostResult<usize> {
+        if evals.is_empty() {
+            return Err(XGBoostError::InvalidInput(
+                "Early stopping needs at least one evaluation matrix".to_owned(),
+            ));
+        }
+        if max_rounds == 0 {
+            return Err(XGBoostError::InvalidInput(
+                "Early stopping needs at least one boosting round".to_owned(),
+            ));
+        }
+        if early_stopping_rounds == 0 {
+            return Err(XGBoostError::InvalidInput(
+                "early_stopping_rounds must be greater than zero".to_owned(),
+            ));
+        }
+
+        let first_iteration = self.num_boosted_rounds()? as i32;
+        let mut best: Option<(i32, f32)> = None;
+
+        for iteration in first_iteration..first_iteration + max_rounds as i32 {
+            self.update_one_iter(iteration, dtrain)?;
+
+            let result = self.eval_set(evals, iteration)?;
+            let (metric, score) = last_eval_metric(&result).ok_or_else(|| {
+                XGBoostError::Native(format!("No evaluation metric in '{}'", result))
+            })?;
+            let maximize = maximize || is_maximized_metric(&metric);
+
+            let improved = match best {
+                None => true,
+                Some((_, best_score)) if maximize => score > best_score,
+                Some((_, best_score)) => score < best_score,
+            };
+            if improved {
+                best = Some((iteration, score));
+            }
+
+            if let Some((best_iteration, _)) = best {
+                if (iteration - best_iteration) as usize >= early_stopping_rounds {
+                    break;
+                }
+            }
+        }
+
+        let (best_iteration, best_score) = best.ok_or_else(|| {
+            XGBoostError::Native("Early stopping recorded no iteration".to_owned())
+        })?;
+        self.set_attr("best_iteration", &best_iteration.to_string())?;
+        self.set_attr("best_score", &best_score.to_string())?;
+
+        Ok(best_iteration as usize)
+    }
+
+    /// Save the model to a file
+    ///
+    /// The format is chosen from the extension, as in the Python package: use `.json`
+    /// for the JSON format that Python's `Booster.save_model` writes and loads. To choose
+    /// the format regardless of the extension, use [`Booster::save_with_format`].
+    ///
+    /// # Arguments
+    /// * `path` - Path where to save the model
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{Booster, BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(5).train(&dtrain).unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("xgboost_rust_save_roundtrip.json");
+    /// booster.save(&path).unwrap();
+    ///
+    /// // The same JSON document Python's `Booster.save_model("model.json")` writes
+    /// let json: serde_json::Value =
+    ///     serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    /// assert!(json.get("learner").is_some());
+    /// assert!(json.get("version").is_some());
+    ///
+    /// let reloaded = Booster::load(&path).unwrap();
+    /// let data = [1.0, 2.0, 3.0, 4.0];
+    /// assert_eq!(
+    ///     reloaded.predict(&data, 2, 2, 0, false).unwrap(),
+    ///     booster.predict(&data, 2, 2, 0, false).unwrap()
+    /// );
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> XGBoostResult<()> {
+        let path_c_str = path_to_c_string(path.as_ref())?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveModel(self.handle, path_c_str.as_ptr())
+        })
+    }
+
+    /// Save the model to a file in the given format, whatever the path's extension
+    ///
+    /// Unlike [`Booster::save`], the extension is ignored, e.g. to write JSON to a
+    /// `.model` file. [`Booster::load`] recognizes each format from the file's contents,
+    /// so the model loads back from the same path. Requires XGBoost ≥ 1.6.
+    ///
+    /// # Arguments
+    /// * `path` - Path where to save the model
+    /// * `format` - Serialization format of the model
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, ModelFormat};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.save_with_format("legacy.model", ModelFormat::Json).unwrap();
+    /// ```
+    #[cfg(xgboost_save_to_buffer)]
+    pub fn save_with_format<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: ModelFormat,
+    ) -> XGBoostResult<()> {
+        let path = path.as_ref();
+        let buffer = self.save_to_buffer(format)?;
+        fs::write(path, buffer)
+            .map_err(|e| XGBoostError::Io(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Dump the model as one string per tree
+    ///
+    /// # Arguments
+    /// * `format` - Output format of each tree dump
+    /// * `with_stats` - Whether to include split statistics (gain, cover)
+    ///
+    /// # Returns
+    /// A vector with one entry per tree
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DumpFormat};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let trees = booster.dump_model(DumpFormat::Text, true).unwrap();
+    /// println!("{}", trees[0]);
+    /// ```
+    pub fn dump_model(&self, format: DumpFormat, with_stats: bool) -> XGBoostResult<Vec<String>> {
+        let fmap = CString::new("").unwrap();
+        let format_c_str = CString::new(format.as_str()).unwrap();
+
+        let mut out_len: u64 = 0;
+        let mut out_dump_array: *mut *const c_char = ptr::null_mut();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterDumpModelEx(
+                self.handle,
+                fmap.as_ptr(),
+                with_stats as i32,
+                format_c_str.as_ptr(),
+                &mut out_len,
+                &mut out_dump_array,
+            )
+        })?;
+
+        unsafe { c_str_array_to_vec(out_dump_array, out_len, "model dump") }
+    }
+
+    /// Dump the model to a directory, writing each tree to its own file
+    ///
+    /// Trees are written to `dir/tree_{i}.txt` or `dir/tree_{i}.json` depending on
+    /// `format`. The directory is created if it does not exist.
+    ///
+    /// # Arguments
+    /// * `dir` - Directory to write the tree files into
+    /// * `format` - Output format of each tree dump
+    /// * `with_stats` - Whether to include split statistics (gain, cover)
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{Booster, DumpFormat};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// booster.dump_model_to_dir("trees", DumpFormat::Json, false).unwrap();
+    /// ```
+    pub fn dump_model_to_dir<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        format: DumpFormat,
+        with_stats: bool,
+    ) -> XGBoostResult<()> {
+        let dir = dir.as_ref();
+        if dir.exists() && !dir.is_dir() {
+            return Err(XGBoostError::InvalidInput(format!(
+                "Path is not a directory: {}",
+                dir.display()
+            )));
+        }
+        fs::create_dir_all(dir).map_err(|e| {
+            XGBoostError::Io(format!(
+                "Failed to create directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let trees = self.dump_model(format, with_stats)?;
+        for (i, tree) in trees.iter().enumerate() {
+            let tree_path = dir.join(format!("tree_{}.{}", i, format.extension()));
+            fs::write(&tree_path, tree).map_err(|e| {
+                XGBoostError::Io(format!("Failed to write {}: {}", tree_path.display(), e))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate the model on one or more matrices
+    ///
+    /// The matrices need labels for the model's evaluation metrics to be computed.
+    ///
+    /// Takes `&mut self`, like [`Booster::update_one_iter`]: XGBoost sets up its metrics
+    /// and caches the predictions of each matrix inside the booster while evaluating, so
+    /// this must not run concurrently with other calls on the same booster.
+    ///
+    /// # Arguments
+    /// * `matrices` - Pairs of matrix and the name to report its metrics under
+    /// * `iteration` - Iteration number, shown as the `[N]` prefix of the result
+    ///
+    /// # Returns
+    /// The raw evaluation string, e.g. `[0]\ttrain-rmse:0.5\ttest-rmse:0.6`.
+    /// Use [`parse_eval_result`] to turn it into a map of metric values, or
+    /// [`Booster::eval_metrics`] to group them by matrix.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::{parse_eval_result, Booster, DMatrix};
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// let test = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// let result = booster.eval_set(&[(&test, "test")], 0).unwrap();
+    /// let metrics = parse_eval_result(&result);
+    /// println!("{:?}", metrics.get("test-rmse"));
+    /// ```
+    pub fn eval_set(
+        &mut self,
+        matrices: &[(&DMatrix, &str)],
+        iteration: i32,
+    ) -> XGBoostResult<String> {
+        let mut handles: Vec<sys::DMatrixHandle> = matrices
+            .iter()
+            .map(|(dmatrix, _)| dmatrix.handle())
+            .collect();
+        let names = matrices
+            .iter()
+            .map(|(_, name)| CString::new(*name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut name_ptrs: Vec<*const c_char> = names.iter().map(|name| name.as_ptr()).collect();
+
+        let mut out_result: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterEvalOneIter(
+                self.handle,
+                iteration,
+                handles.as_mut_ptr(),
+                name_ptrs.as_mut_ptr(),
+                matrices.len() as u64,
+                &mut out_result,
+            )
+        })?;
+
+        unsafe { cstr_to_string(out_result, "evaluation result") }
+    }
+
+    /// Evaluate the model on several named matrices and group the metrics by matrix
+    ///
+    /// Runs [`Booster::eval_set`] once over all the matrices, e.g. one holdout set per
+    /// segment, and splits its result by matrix name, so each matrix's metrics are
+    /// keyed by the metric alone (`rmse` rather than `test-rmse`).
+    ///
+    /// Takes `&mut self` for the same reason as [`Booster::eval_set`]: evaluating caches
+    /// state inside the booster, so it must not run concurrently with other calls on it.
+    ///
+    /// # Arguments
+    /// * `matrices` - Pairs of matrix and its name, which need not be unique
+    /// * `iteration` - Iteration number passed on to [`Booster::eval_set`]
+    ///
+    /// # Returns
+    /// A map from matrix name to a map from metric name to value. Every name in
+    /// `matrices` has an entry.
+    ///
+    /// # Example
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut north = DMatrix::from_dense(&[1.0, 2.5], 1, 2, f32::NAN).unwrap();
+    /// north.set_labels(&[0.0]).unwrap();
+    /// let mut south = DMatrix::from_dense(&[3.0, 4.5], 1, 2, f32::NAN).unwrap();
+    /// south.set_labels(&[1.0]).unwrap();
+    ///
+    /// let mut booster = BoosterBuilder::new()
+    ///     .objective("binary:logistic")
+    ///     .param("eval_metric", "logloss")
+    ///     .num_round(2)
+    ///     .train(&dtrain)
+    ///     .unwrap();
+    ///
+    /// let metrics = booster
+    ///     .eval_metrics(&[(&north, "segment-north"), (&south, "segment-south")], 0)
+    ///     .unwrap();
+    /// assert!(metrics["segment-north"]["logloss"] > 0.0);
+    /// assert!(metrics["segment-south"].contains_key("logloss"));
+    /// ```
+    pub fn eval_metrics(
+        &mut self,
+        matrices: &[(&DMatrix, &str)],
+        iteration: i32,
+    ) -> XGBoostResult<HashMap<String, HashMap<String, f32>>> {
+        let result = self.eval_set(matrices, iteration)?;
+
+        let mut metrics: HashMap<String, HashMap<String, f32>> = matrices
+            .iter()
+            .map(|(_, name)| (name.to_string(), HashMap::new()))
+            .collect();
+        for (key, value) in parse_eval_result(&result) {
+            // Names may contain '-' themselves, so match the longest name that prefixes the key
+            let matched = matrices
+                .iter()
+                .filter_map(|(_, name)| {
+                    let metric = key.strip_prefix(name)?.strip_prefix('-')?;
+                    Some((*name, metric))
+                })
+                .max_by_key(|(name, _)| name.len());
+            if let Some((name, metric)) = matched {
+                if let Some(dataset) = metrics.get_mut(name) {
+                    dataset.insert(metric.to_string(), value);
+                }
+            }
+        }
+
+        Ok(metrics)
+    }
+
+    /// Get the full internal configuration of the booster as JSON
+    ///
+    /// This includes the learner, objective and booster parameters, and can be restored
+    /// with [`Booster::load_config`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// println!("{}", booster.save_config().unwrap());
+    /// ```
+    pub fn save_config(&self) -> XGBoostResult<String> {
+        let mut out_len: u64 = 0;
+        let mut out_str: *const c_char = ptr::null();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSaveJsonConfig(self.handle, &mut out_len, &mut out_str)
+        })?;
+
+        unsafe { cstr_to_string(out_str, "configuration") }
+    }
+
+    /// Load a configuration previously produced by [`Booster::save_config`]
+    ///
+    /// # Arguments
+    /// * `json` - Configuration JSON
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let source = Booster::load("model.json").unwrap();
+    /// let mut booster = Booster::load("other_model.json").unwrap();
+    /// booster.load_config(&source.save_config().unwrap()).unwrap();
+    /// ```
+    pub fn load_config(&mut self, json: &str) -> XGBoostResult<()> {
+        let json_c_str = CString::new(json)?;
+
+        self.is_dart = OnceLock::new();
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterLoadJsonConfig(self.handle, json_c_str.as_ptr())
+        })
+    }
+
+    /// Get an attribute stored in the model
+    ///
+    /// # Returns
+    /// The attribute value, or `None` if the model has no attribute named `key`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// if let Some(best_iteration) = booster.get_attr("best_iteration").unwrap() {
+    ///     println!("best iteration: {}", best_iteration);
+    /// }
+    /// ```
+    ///
+    /// A value that is not valid UTF-8, e.g. written by another tool, gives
+    /// [`XGBoostError::InvalidUtf8`]:
+    ///
+    /// ```
+    /// use xgboost_rust::{BoosterBuilder, DMatrix, XGBoostError};
+    /// # use xgboost_rust::Booster;
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let mut booster = BoosterBuilder::new().num_round(1).train(&dtrain).unwrap();
+    /// booster.set_attr("note", "PLACEHOLDER").unwrap();
+    ///
+    /// // Replace the attribute value with invalid UTF-8 in the saved model
+    /// let path = std::env::temp_dir().join("xgboost_rust_invalid_utf8_attr.json");
+    /// booster.save(&path).unwrap();
+    /// let mut model = std::fs::read(&path).unwrap();
+    /// let start = model.windows(11).position(|w| w == b"PLACEHOLDER").unwrap();
+    /// model.splice(start..start + 11, [0xc3, 0x28]);
+    /// std::fs::write(&path, &model).unwrap();
+    ///
+    /// let booster = Booster::load(&path).unwrap();
+    /// assert!(matches!(booster.get_attr("note"), Err(XGBoostError::InvalidUtf8(_))));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn get_attr(&self, key: &str) -> XGBoostResult<Option<String>> {
+        let key_c_str = CString::new(key)?;
+        let mut out_value: *const c_char = ptr::null();
+        let mut success: i32 = 0;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetAttr(
+                self.handle,
+                key_c_str.as_ptr(),
+                &mut out_value,
+                &mut success,
+            )
+        })?;
+
+        if success == 0 || out_value.is_null() {
+            return Ok(None);
+        }
+        unsafe { cstr_to_string(out_value, "attribute") }.map(Some)
+    }
+
+    /// Store an attribute in the model
+    ///
+    /// Attributes are saved with the model, and are visible to the Python package through
+    /// `Booster.attr`.
+    ///
+    /// # Arguments
+    /// * `key` - Attribute name
+    /// * `value` - Attribute value
+    pub fn set_attr(&mut self, key: &str, value: &str) -> XGBoostResult<()> {
+        let key_c_str = CString::new(key)?;
+        let value_c_str = CString::new(value)?;
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterSetAttr(self.handle, key_c_str.as_ptr(), value_c_str.as_ptr())
+        })
+    }
+
+    /// Get the best iteration recorded by early stopping
+    ///
+    /// Reads the `best_iteration` attribute set by [`Booster::train_with_early_stopping`]
+    /// and by the Python package's early stopping.
+    ///
+    /// # Returns
+    /// The best iteration, or `None` if the model was not trained with early stopping
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// if let Some(best_iteration) = booster.best_iteration().unwrap() {
+    ///     println!("best iteration: {}", best_iteration);
+    /// }
+    /// ```
+    pub fn best_iteration(&self) -> XGBoostResult<Option<usize>> {
+        self.parse_attr("best_iteration")
+    }
+
+    /// Get the best evaluation score recorded by early stopping
+    ///
+    /// Reads the `best_score` attribute set by [`Booster::train_with_early_stopping`]
+    /// and by the Python package's early stopping.
+    ///
+    /// # Returns
+    /// The best score, or `None` if the model was not trained with early stopping
+    pub fn best_score(&self) -> XGBoostResult<Option<f32>> {
+        self.parse_attr("best_score")
+    }
+
+    fn parse_attr<T: std::str::FromStr>(&self, key: &str) -> XGBoostResult<Option<T>> {
+        self.get_attr(key)?
+            .map(|value| {
+                value.trim().parse().map_err(|_| {
+                    XGBoostError::InvalidInput(format!(
+                        "Attribute {} has invalid value {:?}",
+                        key, value
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Get the feature names stored in the model
+    ///
+    /// Models trained through the Python package on a DataFrame store its column names.
+    ///
+    /// # Returns
+    /// One name per feature, or an empty vector if the model has no feature names
+    ///
+    /// # Example
+    /// ```no_run
+    /// use xgboost_rust::Booster;
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let columns = ["age", "income", "score"];
+    /// let names = booster.feature_names().unwrap();
+    /// assert!(names.is_empty() || names == columns);
+    /// ```
+    pub fn feature_names(&self) -> XGBoostResult<Vec<String>> {
+        self.get_str_feature_info("feature_name")
+    }
+
+    /// Get the feature types stored in the model, e.g. `q` (numerical) or `c` (categorical)
+    ///
+    /// # Returns
+    /// One type per feature, or an empty vector if the model has no feature types
+    pub fn feature_types(&self) -> XGBoostResult<Vec<String>> {
+        self.get_str_feature_info("feature_type")
+    }
+
+    /// Get a string feature info field stored in the model (`feature_name` or `feature_type`)
+    ///
+    /// Returns an empty vector when the model has no such information.
+    fn get_str_feature_info(&self, field: &str) -> XGBoostResult<Vec<String>> {
+        let field_c_str = CString::new(field)?;
+
+        let mut out_len: u64 = 0;
+        let mut out_features: *mut *const c_char = ptr::null_mut();
+
+        XGBoostError::check_return_value(unsafe {
+            sys::XGBoosterGetStrFeatureInfo(
+                self.handle,
+                field_c_str.as_ptr(),
+                &mut out_len,
+                &mut out_features,
+            )
+        })?;
+
+        unsafe { c_str_array_to_vec(out_features, out_len, field) }
+    }
+}
+
+/// Parse an evaluation string returned by [`Booster::eval_set`] into metric values
+///
+/// Keys are `{name}-{metric}`, e.g. `test-rmse`. The leading `[N]` iteration marker and
+/// any entries that are not `key:value` pairs are ignored.
+///
+/// # Example
+/// ```
+/// use xgboost_rust::parse_eval_result;
+///
+/// let metrics = parse_eval_result("[3]\ttrain-rmse:0.25\ttest-rmse:0.5");
+/// assert_eq!(metrics["test-rmse"], 0.5);
+/// ```
+pub fn parse_eval_result(result: &str) -> HashMap<String, f32> {
+    result
+        .split_whitespace()
+        .filter(|entry| !entry.starts_with('['))
+        .filter_map(|entry| {
+            let (key, value) = entry.rsplit_once(':')?;
+            Some((key.to_owned(), value.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Get the last `key:value` entry of an evaluation string, as used for early stopping
+fn last_eval_metric(result: &str) -> Option<(String, f32)> {
+    result
+        .split_whitespace()
+        .rev()
+        .filter(|entry| !entry.starts_with('['))
+        .find_map(|entry| {
+            let (key, value) = entry.rsplit_once(':')?;
+            Some((key.to_owned(), value.parse().ok()?))
+        })
+}
+
+/// Check whether higher values are better for an evaluation metric key like `valid-auc`
+fn is_maximized_metric(key: &str) -> bool {
+    let metric = key.rsplit_once('-').map_or(key, |(_, metric)| metric);
+    let base = metric.split('@').next().unwrap_or(metric);
+    matches!(base, "auc" | "aucpr" | "map" | "ndcg" | "pre")
+}
+
+/// Read predictions written by [`Booster::predict_to_writer`]
+///
+/// Values are read lazily, one at a time; wrap `reader` in a `BufReader` for files.
+///
+/// # Example
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let reader = BufReader::new(File::open("predictions.bin").unwrap());
+/// for prediction in xgboost_rust::read_predictions(reader) {
+///     println!("{}", prediction.unwrap());
+/// }
+/// ```
+pub fn read_predictions<R: Read>(reader: R) -> impl Iterator<Item = XGBoostResult<f32>> {
+    PredictionReader {
+        reader,
+        done: false,
+    }
+}
+
+/// Iterator returned by [`read_predictions`]
+struct PredictionReader<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read> Iterator for PredictionReader<R> {
+    type Item = XGBoostResult<f32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut bytes = [0u8; 4];
+        let mut filled = 0;
+        while filled < bytes.len() {
+            match self.reader.read(&mut bytes[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(XGBoostError::Io(format!(
+                        "Failed to read predictions: {}",
+                        e
+                    ))));
+                }
+            }
+        }
+
+        match filled {
+            0 => {
+                self.done = true;
+                None
+            }
+            4 => Some(Ok(f32::from_le_bytes(bytes))),
+            _ => {
+                self.done = true;
+                Some(Err(XGBoostError::Io(format!(
+                    "Failed to read predictions: truncated value ({} of 4 bytes)",
+                    filled
+                ))))
+            }
+        }
+    }
+}
+
+/// Iterator returned by [`Booster::predict_stream`]
+struct PredictStream<'a, I> {
+    booster: &'a Booster,
+    rows: I,
+    batch_rows: usize,
+    option_mask: u32,
+    training: bool,
+    num_features: Option<usize>,
+    batch: Vec<f32>,
+    pending: VecDeque<Vec<f32>>,
+    done: bool,
+}
+
+impl<I: Iterator<Item = Vec<f32>>> PredictStream<'_, I> {
+    /// Pull up to `batch_rows` rows from the input and predict them
+    fn predict_next_batch(&mut self) -> XGBoostResult<()> {
+        self.batch.clear();
+        let mut num_rows = 0;
+
+        while num_rows < self.batch_rows {
+            let row = match self.rows.next() {
+                Some(row) => row,
+                None => break,
+            };
+
+            let num_features = *self.num_features.get_or_insert(row.len());
+            if row.len() != num_features {
+                return Err(XGBoostError::DimensionMismatch {
+                    expected: num_features,
+                    got: row.len(),
+                });
+            }
+
+            self.batch.extend_from_slice(&row);
+            num_rows += 1;
+        }
+
+        if num_rows == 0 {
+            self.done = true;
+            return Ok(());
+        }
+
+        let num_features = self.num_features.unwrap_or(0);
+        let predictions = self.booster.predict(
+            &self.batch,
+            num_rows,
+            num_features,
+            self.option_mask,
+            self.training,
+        )?;
+
+        // Split the flat output back into one chunk per input row
+        let per_row = predictions.len() / num_rows;
+        self.pending
+            .extend(predictions.chunks(per_row.max(1)).map(<[f32]>::to_vec));
+
+        Ok(())
+    }
+}
+
+impl<I: Iterator<Item = Vec<f32>>> Iterator for PredictStream<'_, I> {
+    type Item = XGBoostResult<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() && !self.done {
+            if let Err(e) = self.predict_next_batch() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Read a value from the configuration JSON, which must contain it
+fn config_entry(config: &str, path: &[&str]) -> XGBoostResult<String> {
+    json::lookup(config, path)?
+        .ok_or_else(|| XGBoostError::Native(format!("Model config has no {}", path[1..].join("."))))
+}
+
+/// Read the base score from a config, a single value or a bracketed list of values per
+/// output in newer versions
+fn config_base_score(config: &str) -> XGBoostResult<Vec<f32>> {
+    let base_score = config_entry(config, &["learner", "learner_model_param", "base_score"])?;
+    base_score
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|value| value.trim().parse())
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|_| {
+            XGBoostError::Native(format!(
+                "Invalid base_score {:?} in model config",
+                base_score
+            ))
+        })
+}
+
+/// Read a number from the configuration JSON, where numbers are stored as strings
+fn config_number<T: std::str::FromStr>(config: &str, path: &[&str]) -> XGBoostResult<T> {
+    let value = config_entry(config, path)?;
+    value.trim().parse().map_err(|_| {
+        XGBoostError::Native(format!(
+            "Invalid {} {:?} in model config",
+            path[path.len() - 1],
+            value
+        ))
+    })
+}
+
+/// Convert a path into a C string that can be passed to XGBoost
+pub(crate) fn path_to_c_string(path: &Path) -> XGBoostResult<CString> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| XGBoostError::InvalidPath(path.to_path_buf()))?;
+    Ok(CString::new(path_str)?)
+}
+
+/// Build the `__array_interface__` JSON descriptor XGBoost expects for a dense f32 buffer
+#[cfg(xgboost_inplace_predict)]
+pub(crate) fn dense_array_interface(data: &[f32], num_rows: usize, num_features: usize) -> String {
+    let typestr = if cfg!(target_endian = "little") {
+        "<f4"
+    } else {
+        ">f4"
+    };
+    format!(
+        r#"{{"data": [{}, true], "shape": [{}, {}], "typestr": "{}", "version": 3}}"#,
+        data.as_ptr() as usize,
+        num_rows,
+        num_features,
+        typestr
+    )
+}
+
+/// Build the `__array_interface__` JSON descriptor for a dense column-major f32 buffer
+#[cfg(xgboost_inplace_predict)]
+fn column_major_array_interface(data: &[f32], num_rows: usize, num_features: usize) -> String {
+    let typestr = if cfg!(target_endian = "little") {
+        "<f4"
+    } else {
+        ">f4"
+    };
+    let item_size = std::mem::size_of::<f32>();
+    format!(
+        r#"{{"data": [{}, true], "shape": [{}, {}], "strides": [{}, {}], "typestr": "{}", "version": 3}}"#,
+        data.as_ptr() as usize,
+        num_rows,
+        num_features,
+        item_size,
+        item_size * num_rows,
+        typestr
+    )
+}
+
+/// Format an f32 as a JSON number, using the NaN/Infinity literals XGBoost's parser accepts
+#[cfg(xgboost_inplace_predict)]
+pub(crate) fn json_f32(value: f32) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 {
+            "Infinity".to_string()
+        } else {
+            "-Infinity".to_string()
+        }
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Copy the output of a prediction that reports its shape into owned buffers
+///
+/// # Safety
+/// The pointers must be the outputs of a successful XGBoost prediction call, and not yet
+/// invalidated by another call on the same booster.
+#[cfg(xgboost_predict_from_dmatrix)]
+unsafe fn copy_shaped_result(
+    out_shape: *const u64,
+    out_dim: u64,
+    out_result: *const f32,
+) -> XGBoostResult<(Vec<f32>, Vec<usize>)> {
+    if out_shape.is_null() {
+        return Err(XGBoostError::Native(
+            "XGBoost returned null prediction shape".to_string(),
+        ));
+    }
+
+    // The output length is the product of the output shape
+    let shape: Vec<usize> = std::slice::from_raw_parts(out_shape, out_dim as usize)
+        .iter()
+        .map(|&dim| dim as usize)
+        .collect();
+    let out_len: usize = shape.iter().product();
+
+    // An empty result may come with a null pointer, which must not become a slice
+    if out_len == 0 {
+        return Ok((Vec::new(), shape));
+    }
+    if out_result.is_null() {
+        return Err(XGBoostError::Native(
+            "XGBoost returned null prediction result".to_string(),
+        ));
+    }
+
+    let results = std::slice::from_raw_parts(out_result, out_len).to_vec();
+    Ok((results, shape))
+}
+
+/// Copy a C string returned by XGBoost into an owned Rust string
+///
+/// Strings that are not valid UTF-8 give [`XGBoostError::InvalidUtf8`], and a null
+/// pointer gives an error naming `what`.
+///
+/// # Safety
+/// `ptr` must be null or point to a valid NUL-terminated string.
+pub(crate) unsafe fn cstr_to_string(ptr: *const c_char, what: &str) -> XGBoostResult<String> {
+    if ptr.is_null() {
+        return Err(XGBoostError::Native(format!(
+            "XGBoost returned null {}",
+            what
+        )));
+    }
+    Ok(CStr::from_ptr(ptr).to_str()?.to_owned())
+}
+
+/// Copy an array of C strings returned by XGBoost into owned Rust strings
+///
+/// # Safety
+/// `ptrs` must be null or point to `len` valid NUL-terminated strings.
+unsafe fn c_str_array_to_vec(
+    ptrs: *mut *const c_char,
+    len: u64,
+    what: &str,
+) -> XGBoostResult<Vec<String>> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    if ptrs.is_null() {
+        return Err(XGBoostError::Native(format!(
+            "XGBoost returned null {}",
+            what
+        )));
+    }
+
+    std::slice::from_raw_parts(ptrs, len as usize)
+        .iter()
+        .map(|&str_ptr| cstr_to_string(str_ptr, what))
+        .collect()
+}
+
+/// Clones the booster with [`Booster::try_clone`], panicking if XGBoost fails to copy the model
+#[cfg(xgboost_save_to_buffer)]
+impl Clone for Booster {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to clone Booster")
+    }
+}
+
+/// Summarizes the model instead of printing the raw handle
+///
+/// Shows the number of features and boosted rounds, the objective and the booster type,
+/// read from a single [`Booster::save_config`]. A value XGBoost fails to report is
+/// printed as `unknown`, so formatting never panics.
+///
+/// # Example
+/// ```
+/// use xgboost_rust::{Booster, BoosterBuilder, DMatrix};
+///
+/// #[derive(Debug)]
+/// struct Scorer {
+///     name: String,
+///     booster: Booster,
+/// }
+///
+/// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+/// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+/// let booster = BoosterBuilder::new()
+///     .objective("binary:logistic")
+///     .num_round(3)
+///     .train(&dtrain)
+///     .unwrap();
+///
+/// let scorer = Scorer { name: "churn".to_string(), booster };
+/// assert_eq!(
+///     format!("{:?}", scorer.booster),
+///     "Booster { num_features: 2, num_boosted_rounds: 3, objective: binary:logistic, booster: gbtree }"
+/// );
+/// println!("{:?}", scorer);
+/// ```
+impl fmt::Debug for Booster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_unknown<T: fmt::Display>(value: XGBoostResult<T>) -> String {
+            value.map_or_else(|_| "unknown".to_string(), |value| value.to_string())
+        }
+
+        let config = self.save_config();
+        let from_config = |path: &[&str]| {
+            or_unknown(
+                config
+                    .as_deref()
+                    .map_err(Clone::clone)
+                    .and_then(|config| config_entry(config, path)),
+            )
+        };
+
+        f.debug_struct("Booster")
+            .field(
+                "num_features",
+                &format_args!("{}", or_unknown(self.num_features())),
+            )
+            .field(
+                "num_boosted_rounds",
+                &format_args!("{}", or_unknown(self.num_boosted_rounds())),
+            )
+            .field(
+                "objective",
+                &format_args!("{}", from_config(&["learner", "objective", "name"])),
+            )
+            .field(
+                "booster",
+                &format_args!("{}", from_config(&["learner", "gradient_booster", "name"])),
+            )
+            .finish()
     }
 }
 
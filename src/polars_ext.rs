@@ -0,0 +1,797 @@
+use crate::dmatrix::DMatrix;
+use crate::error::{XGBoostError, XGBoostResult};
+use crate::model::{Booster, PredictConfig};
+use polars::prelude::*;
+use std::fs::File;
+use std::path::Path;
+
+/// Polars integration for [`Booster`]
+///
+/// Enabled with the `polars` feature.
+pub trait BoosterPolarsExt {
+    /// Make predictions on every column of a DataFrame, in column order
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame of numeric feature columns
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `validate_feature_names` - Whether to check the column names against the feature
+    ///   names stored in the model, and pass them to XGBoost as feature names
+    ///
+    /// When `validate_feature_names` is set and the model has feature names, an error is
+    /// returned if the columns do not match them exactly, in order. This catches frames
+    /// whose columns were reordered or renamed since training.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{Booster, BoosterPolarsExt};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let df = df!("a" => [1.0f32, 2.0], "b" => [3.0f32, 4.0]).unwrap();
+    /// let predictions = booster.predict_dataframe(&df, 0, false, true).unwrap();
+    /// ```
+    fn predict_dataframe(
+        &self,
+        df: &DataFrame,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Make predictions on the named columns of a DataFrame, in the given order
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame containing the feature columns
+    /// * `columns` - Names of the feature columns, in the order the model expects
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `validate_feature_names` - Whether to check `columns` against the feature names
+    ///   stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    fn predict_dataframe_with_columns(
+        &self,
+        df: &DataFrame,
+        columns: &[&str],
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Make predictions on the named columns of a LazyFrame, in the given order
+    ///
+    /// Only `columns` are selected before the frame is collected, so the other columns
+    /// are never materialized.
+    ///
+    /// # Arguments
+    /// * `lf` - LazyFrame containing the feature columns
+    /// * `columns` - Names of the feature columns, in the order the model expects
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `validate_feature_names` - Whether to check `columns` against the feature names
+    ///   stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{Booster, BoosterPolarsExt};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let lf = LazyCsvReader::new("features.csv").finish().unwrap();
+    /// let predictions = booster
+    ///     .predict_lazyframe(lf, &["a", "b", "c"], 0, false, true)
+    ///     .unwrap();
+    /// ```
+    fn predict_lazyframe(
+        &self,
+        lf: LazyFrame,
+        columns: &[&str],
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Make predictions on every column of a DataFrame, converting `chunk_rows` rows at a time
+    ///
+    /// Gives the same predictions as [`BoosterPolarsExt::predict_dataframe`], but only one
+    /// chunk is converted to a dense buffer at a time, so the conversion needs about
+    /// `chunk_rows * df.width() * 4` bytes instead of a copy of the whole frame.
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame of numeric feature columns
+    /// * `chunk_rows` - Number of rows to convert and predict at a time, greater than zero
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `validate_feature_names` - Whether to check the column names against the feature
+    ///   names stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{Booster, BoosterPolarsExt};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let df = CsvReadOptions::default()
+    ///     .try_into_reader_with_file_path(Some("features.csv".into()))
+    ///     .unwrap()
+    ///     .finish()
+    ///     .unwrap();
+    /// let predictions = booster
+    ///     .predict_dataframe_chunked(&df, 100_000, 0, false, true)
+    ///     .unwrap();
+    /// ```
+    fn predict_dataframe_chunked(
+        &self,
+        df: &DataFrame,
+        chunk_rows: usize,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Make predictions on a DataFrame converted with [`PreparedFrame::new`]
+    ///
+    /// # Arguments
+    /// * `frame` - The converted DataFrame
+    /// * `option_mask` - Prediction options (see `predict_option` module)
+    /// * `training` - Whether this is for training (false for inference, see [`Booster::predict`])
+    /// * `validate_feature_names` - Whether to check the column names against the feature
+    ///   names stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    fn predict_prepared(
+        &self,
+        frame: &PreparedFrame,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Make predictions on a DataFrame with per-call options
+    ///
+    /// See [`Booster::predict_with_config`]. This is how to limit the boosting rounds
+    /// used, e.g. to the best iteration found by early stopping.
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame containing the feature columns
+    /// * `columns` - Names of the feature columns, in the order the model expects, or
+    ///   `None` to use every column in column order
+    /// * `config` - Prediction options
+    /// * `validate_feature_names` - Whether to check the column names against the feature
+    ///   names stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{Booster, BoosterPolarsExt, PredictConfig};
+    ///
+    /// let mut booster = Booster::load("model.json").unwrap();
+    /// let df = df!("a" => [1.0f32, 2.0], "b" => [3.0f32, 4.0]).unwrap();
+    /// let config = PredictConfig {
+    ///     iteration_end: booster.best_iteration().unwrap().map(|best| best + 1),
+    ///     ..Default::default()
+    /// };
+    /// let predictions = booster
+    ///     .predict_dataframe_with_config(&df, Some(&["a", "b"]), &config, true)
+    ///     .unwrap();
+    /// ```
+    fn predict_dataframe_with_config(
+        &mut self,
+        df: &DataFrame,
+        columns: Option<&[&str]>,
+        config: &PredictConfig,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>>;
+
+    /// Convert a DataFrame into a [`DMatrix`] for this model, to predict on it repeatedly
+    ///
+    /// `predict_dataframe` converts the frame on every call. Converting once and calling
+    /// [`Booster::predict_dmatrix`] instead avoids that when the same frame is predicted
+    /// several times, e.g. with different option masks. The matrix can also be passed to
+    /// other models with the same features.
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame containing the feature columns
+    /// * `columns` - Names of the feature columns, in the order the model expects, or
+    ///   `None` to use every column in column order
+    /// * `validate_feature_names` - Whether to check the column names against the feature
+    ///   names stored in the model (see [`BoosterPolarsExt::predict_dataframe`])
+    ///
+    /// # Example
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{predict_option, Booster, BoosterPolarsExt};
+    ///
+    /// let booster = Booster::load("model.json").unwrap();
+    /// let df = df!("id" => [1, 2], "a" => [1.0f32, 2.0], "b" => [3.0f32, 4.0]).unwrap();
+    /// let dmatrix = booster
+    ///     .dataframe_to_dmatrix(&df, Some(&["a", "b"]), true)
+    ///     .unwrap();
+    /// let predictions = booster.predict_dmatrix(&dmatrix, 0, false).unwrap();
+    /// let contributions = booster
+    ///     .predict_dmatrix(&dmatrix, predict_option::PRED_CONTRIBS, false)
+    ///     .unwrap();
+    /// ```
+    fn dataframe_to_dmatrix(
+        &self,
+        df: &DataFrame,
+        columns: Option<&[&str]>,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<DMatrix>;
+
+    /// Make predictions on the named columns of a DataFrame and add them to a copy of it
+    ///
+    /// The predictions become a `Float32` column named `output_name`, or for models with
+    /// several outputs per row, such as `multi:softprob`, one column per output named
+    /// `{output_name}_0`, `{output_name}_1`, ... An existing column with the same name is
+    /// replaced. The column names are checked against the feature names stored in the
+    /// model (see [`BoosterPolarsExt::predict_dataframe`]).
+    ///
+    /// # Arguments
+    /// * `df` - DataFrame containing the feature columns
+    /// * `feature_columns` - Names of the feature columns, in the order the model expects
+    /// * `output_name` - Name of the prediction column
+    ///
+    /// # Example
+    /// ```
+    /// use polars::prelude::*;
+    /// use xgboost_rust::{BoosterBuilder, BoosterPolarsExt, DMatrix};
+    ///
+    /// let mut dtrain = DMatrix::from_dense(&[1.0, 2.0, 3.0, 4.0], 2, 2, f32::NAN).unwrap();
+    /// dtrain.set_labels(&[0.0, 1.0]).unwrap();
+    /// let booster = BoosterBuilder::new().num_round(2).train(&dtrain).unwrap();
+    ///
+    /// let df = df!("id" => ["x", "y"], "a" => [1.0f32, 3.0], "b" => [2.0f32, 4.0]).unwrap();
+    /// let scored = booster.predict_append(&df, &["a", "b"], "score").unwrap();
+    /// assert_eq!(scored.get_column_names(), ["id", "a", "b", "score"]);
+    /// ```
+    fn predict_append(
+        &self,
+        df: &DataFrame,
+        feature_columns: &[&str],
+        output_name: &str,
+    ) -> XGBoostResult<DataFrame>;
+}
+
+impl BoosterPolarsExt for Booster {
+    fn predict_dataframe(
+        &self,
+        df: &DataFrame,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let dmatrix = prediction_dmatrix(self, df, validate_feature_names)?;
+        self.predict_dmatrix(&dmatrix, option_mask, training)
+    }
+
+    fn predict_dataframe_with_columns(
+        &self,
+        df: &DataFrame,
+        columns: &[&str],
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let selected = select_columns(df, columns)?;
+        self.predict_dataframe(&selected, option_mask, training, validate_feature_names)
+    }
+
+    fn predict_lazyframe(
+        &self,
+        lf: LazyFrame,
+        columns: &[&str],
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let selected = lf
+            .select(columns.iter().map(|&name| col(name)).collect::<Vec<_>>())
+            .collect()
+            .map_err(polars_error)?;
+        self.predict_dataframe(&selected, option_mask, training, validate_feature_names)
+    }
+
+    fn predict_dataframe_chunked(
+        &self,
+        df: &DataFrame,
+        chunk_rows: usize,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        if chunk_rows == 0 {
+            return Err(XGBoostError::InvalidInput(
+                "chunk_rows must be greater than zero".to_owned(),
+            ));
+        }
+
+        let mut predictions = Vec::new();
+        for offset in (0..df.height()).step_by(chunk_rows) {
+            let chunk = df.slice(offset as i64, chunk_rows);
+            predictions.extend(self.predict_dataframe(
+                &chunk,
+                option_mask,
+                training,
+                validate_feature_names,
+            )?);
+        }
+        Ok(predictions)
+    }
+
+    fn predict_prepared(
+        &self,
+        frame: &PreparedFrame,
+        option_mask: u32,
+        training: bool,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        if validate_feature_names {
+            let names: Vec<&str> = frame.column_names.iter().map(String::as_str).collect();
+            check_feature_names(self, &names)?;
+        }
+        self.predict_dmatrix(&frame.dmatrix, option_mask, training)
+    }
+
+    fn predict_dataframe_with_config(
+        &mut self,
+        df: &DataFrame,
+        columns: Option<&[&str]>,
+        config: &PredictConfig,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<Vec<f32>> {
+        let dmatrix = self.dataframe_to_dmatrix(df, columns, validate_feature_names)?;
+        self.predict_dmatrix_with_config(&dmatrix, config)
+    }
+
+    fn dataframe_to_dmatrix(
+        &self,
+        df: &DataFrame,
+        columns: Option<&[&str]>,
+        validate_feature_names: bool,
+    ) -> XGBoostResult<DMatrix> {
+        match columns {
+            Some(columns) => {
+                let selected = select_columns(df, columns)?;
+                prediction_dmatrix(self, &selected, validate_feature_names)
+            }
+            None => prediction_dmatrix(self, df, validate_feature_names),
+        }
+    }
+
+    fn predict_append(
+        &self,
+        df: &DataFrame,
+        feature_columns: &[&str],
+        output_name: &str,
+    ) -> XGBoostResult<DataFrame> {
+        let predictions =
+            self.predict_dataframe_with_columns(df, feature_columns, 0, false, true)?;
+        let mut scored = df.clone();
+        append_predictions(&mut scored, &predictions, output_name)?;
+        Ok(scored)
+    }
+}
+
+/// How float NaN values in a DataFrame are converted
+///
+/// Polars tells nulls (no value) apart from NaN (a float value), but XGBoost does not: it
+/// treats every NaN as a missing value, whatever `missing` value the matrix was built
+/// with. A NaN can therefore not be kept as a feature value, only treated as missing or
+/// rejected. Nulls are always converted to missing values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanHandling {
+    /// Treat NaN like null, as a missing value
+    #[default]
+    Missing,
+    /// Return an error naming the first column and row holding a NaN
+    Error,
+}
+
+/// Convert a DataFrame into a dense row-major `f32` buffer
+///
+/// Every column is cast to `Float32`, except `Enum` columns, which are written as their
+/// integer category codes, and `Boolean` columns, which are written as `1.0` for `true`
+/// and `0.0` for `false`. Null values are written as `f32::NAN`, which
+/// XGBoost treats as missing. So are NaN values, which are thereby indistinguishable from
+/// nulls; use [`dataframe_to_dense_with_nan_handling`] to reject them instead.
+///
+/// # Categorical columns
+/// Only `Enum` columns are accepted as categorical features, and `Categorical` columns
+/// return an error. The codes of a `Categorical` column follow the order in which that
+/// frame (or the global string cache) first saw each category, so the same category can
+/// get one code at training time and another at inference time, silently changing the
+/// predictions. An `Enum` has a fixed list of categories, so its codes are the same in
+/// every frame: cast categorical columns to an `Enum` of the categories the model was
+/// trained with.
+///
+/// # Returns
+/// A buffer of `df.height() * df.width()` values
+///
+/// # Example
+/// ```
+/// use polars::prelude::*;
+/// use xgboost_rust::dataframe_to_dense;
+///
+/// let df = df! {
+///     "active" => [Some(true), Some(false), None],
+///     "score" => [Some(0.25f32), Some(1.5), Some(-2.0)],
+/// }
+/// .unwrap();
+///
+/// let dense = dataframe_to_dense(&df).unwrap();
+/// assert_eq!(dense.len(), 6);
+/// assert_eq!(&dense[..4], &[1.0, 0.25, 0.0, 1.5]);
+/// assert!(dense[4].is_nan());
+/// assert_eq!(dense[5], -2.0);
+/// ```
+pub fn dataframe_to_dense(df: &DataFrame) -> XGBoostResult<Vec<f32>> {
+    dataframe_to_dense_with_nan_handling(df, NanHandling::Missing)
+}
+
+/// Convert a DataFrame into a dense row-major `f32` buffer, choosing how NaN values are
+/// handled
+///
+/// Values are converted as in [`dataframe_to_dense`], and NaN values as `nan_handling`
+/// says.
+///
+/// # Example
+/// ```
+/// use polars::prelude::*;
+/// use xgboost_rust::{dataframe_to_dense_with_nan_handling, NanHandling};
+///
+/// let with_null = df!("a" => [Some(1.0f32), None]).unwrap();
+/// let data = dataframe_to_dense_with_nan_handling(&with_null, NanHandling::Error).unwrap();
+/// assert!(data[1].is_nan());
+///
+/// let with_nan = df!("a" => [1.0f32, f32::NAN]).unwrap();
+/// let error = dataframe_to_dense_with_nan_handling(&with_nan, NanHandling::Error);
+/// assert!(error.is_err());
+/// ```
+pub fn dataframe_to_dense_with_nan_handling(
+    df: &DataFrame,
+    nan_handling: NanHandling,
+) -> XGBoostResult<Vec<f32>> {
+    let num_rows = df.height();
+    let num_cols = df.width();
+    let mut data = vec![f32::NAN; num_rows * num_cols];
+
+    for (col_idx, column) in df.get_columns().iter().enumerate() {
+        let series = column_to_f32(column)?;
+        let ca = series.f32().map_err(polars_error)?;
+
+        if nan_handling == NanHandling::Error {
+            if let Some(row_idx) = ca.iter().position(|value| value.is_some_and(f32::is_nan)) {
+                return Err(XGBoostError::InvalidInput(format!(
+                    "Column '{}' has a NaN value at row {}, which XGBoost would treat as missing",
+                    column.name(),
+                    row_idx
+                )));
+            }
+        }
+
+        // Fast path: a single null-free chunk can be read as a plain slice
+        if let Ok(values) = ca.cont_slice() {
+            for (row_idx, &value) in values.iter().enumerate() {
+                data[row_idx * num_cols + col_idx] = value;
+            }
+        } else {
+            for (row_idx, value) in ca.iter().enumerate() {
+                data[row_idx * num_cols + col_idx] = value.unwrap_or(f32::NAN);
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+/// Convert a DataFrame into a [`DMatrix`]
+///
+/// Values are converted as in [`dataframe_to_dense`]. When the frame has `Enum` columns,
+/// the feature types of the matrix are set so that those columns are treated as
+/// categorical (`c`) and all others as numerical (`q`). `Categorical` columns return an
+/// error, as their category codes are not stable between frames; see the
+/// [categorical columns](dataframe_to_dense#categorical-columns) of [`dataframe_to_dense`].
+///
+/// # Example
+/// ```
+/// use polars::prelude::*;
+/// use xgboost_rust::dataframe_to_dmatrix;
+///
+/// // The categories the model was trained with, in a fixed order
+/// let categories = StringChunked::new("".into(), ["x", "y", "z"]);
+/// let enum_dtype = create_enum_dtype(categories.downcast_iter().next().unwrap().clone());
+///
+/// let mut df = df!("a" => [1.0f32, 2.0], "b" => ["z", "x"]).unwrap();
+/// let b = df.column("b").unwrap().cast(&enum_dtype).unwrap();
+/// df.with_column(b).unwrap();
+/// let dmatrix = dataframe_to_dmatrix(&df).unwrap();
+/// assert_eq!(dmatrix.num_row().unwrap(), 2);
+///
+/// // A Categorical column is rejected, as its codes depend on the frame
+/// let categorical = DataType::Categorical(None, Default::default());
+/// let mut df = df!("a" => [1.0f32, 2.0], "b" => ["z", "x"]).unwrap();
+/// let b = df.column("b").unwrap().cast(&categorical).unwrap();
+/// df.with_column(b).unwrap();
+/// assert!(dataframe_to_dmatrix(&df).is_err());
+/// ```
+pub fn dataframe_to_dmatrix(df: &DataFrame) -> XGBoostResult<DMatrix> {
+    dataframe_to_dmatrix_with_nan_handling(df, NanHandling::Missing)
+}
+
+/// Convert a DataFrame into a [`DMatrix`], choosing how NaN values are handled
+///
+/// See [`dataframe_to_dmatrix`] and [`NanHandling`].
+pub fn dataframe_to_dmatrix_with_nan_handling(
+    df: &DataFrame,
+    nan_handling: NanHandling,
+) -> XGBoostResult<DMatrix> {
+    let data = dataframe_to_dense_with_nan_handling(df, nan_handling)?;
+    let mut dmatrix = DMatrix::from_dense(&data, df.height(), df.width(), f32::NAN)?;
+
+    let feature_types: Vec<&str> = df
+        .get_columns()
+        .iter()
+        .map(|column| {
+            if is_categorical(column.dtype()) {
+                "c"
+            } else {
+                "q"
+            }
+        })
+        .collect();
+    if feature_types.contains(&"c") {
+        dmatrix.set_feature_types(&feature_types)?;
+    }
+
+    Ok(dmatrix)
+}
+
+/// A DataFrame converted once, for predicting with several models
+///
+/// Converting a frame casts every column and copies it into a dense matrix. When the same
+/// frame is scored by several models, convert it once with [`PreparedFrame::new`] and
+/// predict with [`BoosterPolarsExt::predict_prepared`], which gives the same predictions
+/// as [`BoosterPolarsExt::predict_dataframe`].
+///
+/// # Example
+/// ```no_run
+/// use polars::prelude::*;
+/// use xgboost_rust::{Booster, BoosterPolarsExt, PreparedFrame};
+///
+/// let df = df!("a" => [1.0f32, 2.0], "b" => [3.0f32, 4.0]).unwrap();
+/// let frame = PreparedFrame::new(&df).unwrap();
+/// for path in ["model_a.json", "model_b.json"] {
+///     let booster = Booster::load(path).unwrap();
+///     let predictions = booster.predict_prepared(&frame, 0, false, true).unwrap();
+/// }
+/// ```
+pub struct PreparedFrame {
+    dmatrix: DMatrix,
+    column_names: Vec<String>,
+}
+
+impl PreparedFrame {
+    /// Convert every column of a DataFrame, in column order, as in [`dataframe_to_dmatrix`]
+    pub fn new(df: &DataFrame) -> XGBoostResult<Self> {
+        Ok(PreparedFrame {
+            dmatrix: dataframe_to_dmatrix(df)?,
+            column_names: df
+                .get_column_names()
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        })
+    }
+
+    /// Get the names of the converted columns, which are checked against a model's
+    /// feature names when predicting
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    /// Get the converted matrix
+    pub fn dmatrix(&self) -> &DMatrix {
+        &self.dmatrix
+    }
+}
+
+/// Score a CSV file with a saved model and write the predictions to a new CSV file
+///
+/// The output contains the input columns followed by a `prediction` column, or one
+/// `prediction_{i}` column per output for multi-output models (e.g. multiclass).
+///
+/// Feature columns are chosen in this order:
+/// 1. `feature_columns`, when given
+/// 2. The feature names stored in the model, when it has them
+/// 3. Every column of the input file
+///
+/// If none of the header fields of the input look like column names (they all parse as
+/// numbers), the file is read as headerless and columns are named `column_1`, `column_2`, ...
+///
+/// # Arguments
+/// * `model_path` - Path to the model file
+/// * `input_csv` - Path to the CSV file to score
+/// * `output_csv` - Path where to write the scored CSV file
+/// * `feature_columns` - Names of the feature columns, in the order the model expects
+///
+/// # Example
+/// ```no_run
+/// xgboost_rust::score_file("model.json", "input.csv", "scored.csv", None).unwrap();
+/// ```
+pub fn score_file<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+    model_path: P,
+    input_csv: Q,
+    output_csv: R,
+    feature_columns: Option<&[&str]>,
+) -> XGBoostResult<()> {
+    let booster = Booster::load(model_path)?;
+
+    let mut df = read_csv(input_csv.as_ref(), true)?;
+    let has_header = df
+        .get_column_names()
+        .iter()
+        .any(|name| name.parse::<f64>().is_err());
+    if !has_header {
+        df = read_csv(input_csv.as_ref(), false)?;
+    }
+
+    let model_feature_names = booster.feature_names()?;
+    let columns: Vec<&str> = match feature_columns {
+        Some(columns) => columns.to_vec(),
+        None if !model_feature_names.is_empty() => {
+            model_feature_names.iter().map(String::as_str).collect()
+        }
+        None => df
+            .get_column_names()
+            .iter()
+            .map(|name| name.as_str())
+            .collect(),
+    };
+
+    let predictions = booster.predict_dataframe_with_columns(&df, &columns, 0, false, false)?;
+    append_predictions(&mut df, &predictions, "prediction")?;
+
+    let output_csv = output_csv.as_ref();
+    let mut file = File::create(output_csv).map_err(|e| {
+        XGBoostError::Io(format!("Failed to create {}: {}", output_csv.display(), e))
+    })?;
+    CsvWriter::new(&mut file)
+        .include_header(true)
+        .finish(&mut df)
+        .map_err(polars_error)?;
+
+    Ok(())
+}
+
+/// Add `predictions` to `df` as a column named `name`, or as one `{name}_{i}` column per
+/// output when there are several per row
+fn append_predictions(df: &mut DataFrame, predictions: &[f32], name: &str) -> XGBoostResult<()> {
+    let num_rows = df.height();
+    let num_outputs = predictions.len().checked_div(num_rows).unwrap_or(1);
+    if num_outputs == 1 {
+        df.with_column(Column::new(name.into(), predictions))
+            .map_err(polars_error)?;
+    } else {
+        for output_idx in 0..num_outputs {
+            let values: Vec<f32> = predictions
+                .iter()
+                .skip(output_idx)
+                .step_by(num_outputs)
+                .copied()
+                .collect();
+            df.with_column(Column::new(
+                format!("{}_{}", name, output_idx).into(),
+                values,
+            ))
+            .map_err(polars_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Convert a DataFrame for prediction, checking its column names against the model's
+/// feature names when `validate_feature_names` is set
+fn prediction_dmatrix(
+    booster: &Booster,
+    df: &DataFrame,
+    validate_feature_names: bool,
+) -> XGBoostResult<DMatrix> {
+    let mut dmatrix = dataframe_to_dmatrix(df)?;
+    if validate_feature_names {
+        let names: Vec<&str> = df
+            .get_column_names()
+            .iter()
+            .map(|name| name.as_str())
+            .collect();
+        check_feature_names(booster, &names)?;
+        dmatrix.set_feature_names(&names)?;
+    }
+    Ok(dmatrix)
+}
+
+/// Check `names` against the feature names stored in the model, if it has any
+fn check_feature_names(booster: &Booster, names: &[&str]) -> XGBoostResult<()> {
+    let model_names = booster.feature_names()?;
+    if model_names.is_empty() || model_names == names {
+        return Ok(());
+    }
+
+    Err(XGBoostError::InvalidInput(format!(
+        "Feature names mismatch: model expects {:?}, got {:?}",
+        model_names, names
+    )))
+}
+
+/// Select `columns` from `df` in order, with a clear error for missing columns
+fn select_columns(df: &DataFrame, columns: &[&str]) -> XGBoostResult<DataFrame> {
+    if let Some(missing) = columns
+        .iter()
+        .find(|name| df.get_column_index(name).is_none())
+    {
+        return Err(XGBoostError::InvalidInput(format!(
+            "Column '{}' not found in DataFrame",
+            missing
+        )));
+    }
+
+    df.select(columns.iter().copied()).map_err(polars_error)
+}
+
+/// Cast a column to `Float32`, using the category codes of `Enum` columns
+fn column_to_f32(column: &Column) -> XGBoostResult<Series> {
+    let series = column.as_materialized_series();
+    if matches!(series.dtype(), DataType::Categorical(..)) {
+        return Err(XGBoostError::InvalidInput(format!(
+            "Column '{}' is Categorical, whose category codes depend on the frame; \
+             cast it to an Enum of the categories the model was trained with",
+            column.name()
+        )));
+    }
+    let series = if is_categorical(series.dtype()) {
+        series
+            .categorical()
+            .map_err(polars_error)?
+            .physical()
+            .clone()
+            .into_series()
+    } else if series.dtype() == &DataType::Boolean {
+        // Map booleans explicitly rather than relying on the cast, keeping nulls as nulls
+        series
+            .bool()
+            .map_err(polars_error)?
+            .into_iter()
+            .map(|value| value.map(|flag| if flag { 1.0 } else { 0.0 }))
+            .collect::<Float32Chunked>()
+            .with_name(series.name().clone())
+            .into_series()
+    } else {
+        series.clone()
+    };
+
+    series.cast(&DataType::Float32).map_err(|e| {
+        XGBoostError::Polars(format!(
+            "Cannot convert column '{}' to f32: {}",
+            column.name(),
+            e
+        ))
+    })
+}
+
+/// Check whether a column is passed as a categorical feature, which only `Enum` columns are
+fn is_categorical(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::Enum(..))
+}
+
+fn read_csv(path: &Path, has_header: bool) -> XGBoostResult<DataFrame> {
+    if !path.exists() {
+        return Err(XGBoostError::FileNotFound(path.to_path_buf()));
+    }
+
+    CsvReadOptions::default()
+        .with_has_header(has_header)
+        .try_into_reader_with_file_path(Some(path.to_path_buf()))
+        .and_then(|reader| reader.finish())
+        .map_err(polars_error)
+}
+
+fn polars_error(e: PolarsError) -> XGBoostError {
+    XGBoostError::Polars(e.to_string())
+}
@@ -0,0 +1,103 @@
+use crate::dmatrix::Shape;
+
+/// Predictions with the shape and options they were made with
+///
+/// Returned by [`Booster::predict_typed`](crate::Booster::predict_typed). The values are
+/// stored row-major, with one row per input row.
+///
+/// # Example
+/// ```
+/// use xgboost_rust::Predictions;
+///
+/// // 2 rows of 3 class probabilities
+/// let predictions = Predictions::new(vec![0.1, 0.7, 0.2, 0.5, 0.3, 0.2], 2, 0);
+/// assert_eq!(predictions.num_cols(), 3);
+/// assert_eq!(predictions.as_2d()[1], &[0.5, 0.3, 0.2]);
+/// assert_eq!(predictions.argmax_per_row(), vec![1, 0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predictions {
+    values: Vec<f32>,
+    shape: Shape,
+    option_mask: u32,
+}
+
+impl Predictions {
+    /// Wrap a flat row-major prediction buffer
+    ///
+    /// # Arguments
+    /// * `values` - Predictions (row-major), the same number for every row
+    /// * `num_rows` - Number of rows the predictions were made for
+    /// * `option_mask` - Prediction options the predictions were made with
+    pub fn new(values: Vec<f32>, num_rows: usize, option_mask: u32) -> Self {
+        let shape = Shape {
+            rows: num_rows,
+            cols: values.len().checked_div(num_rows).unwrap_or(0),
+        };
+        Predictions {
+            values,
+            shape,
+            option_mask,
+        }
+    }
+
+    /// Get the number of rows
+    pub fn num_rows(&self) -> usize {
+        self.shape.rows
+    }
+
+    /// Get the number of values predicted for each row, e.g. one per class for
+    /// `multi:softprob` or one per feature plus bias for `PRED_CONTRIBS`
+    pub fn num_cols(&self) -> usize {
+        self.shape.cols
+    }
+
+    /// Get the shape of the predictions
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /// Get the prediction options the predictions were made with (see `predict_option` module)
+    pub fn option_mask(&self) -> u32 {
+        self.option_mask
+    }
+
+    /// Get the predictions as one flat row-major slice
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Get the predictions of each row
+    pub fn as_2d(&self) -> Vec<&[f32]> {
+        if self.shape.cols == 0 {
+            return vec![&[]; self.shape.rows];
+        }
+        self.values.chunks(self.shape.cols).collect()
+    }
+
+    /// Get the column with the highest value in each row, e.g. the most likely class
+    ///
+    /// Intended for outputs with one column per class, such as `multi:softprob` or
+    /// margins of a multiclass model. Outputs with a single column, such as binary
+    /// probabilities, always give 0; compare them against a threshold instead.
+    /// NaN values are never picked unless a row contains nothing else.
+    pub fn argmax_per_row(&self) -> Vec<usize> {
+        self.as_2d()
+            .into_iter()
+            .map(|row| {
+                let mut best = 0;
+                for (col, &value) in row.iter().enumerate() {
+                    if value > row[best] || row[best].is_nan() {
+                        best = col;
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Take the flat row-major prediction buffer
+    pub fn into_vec(self) -> Vec<f32> {
+        self.values
+    }
+}